@@ -2,6 +2,7 @@ use std::{env, path::Path};
 
 fn main() {
     println!("cargo:rerun-if-changed=ui/app.slint");
+    println!("cargo:rerun-if-changed=ui/details.slint");
     slint_build::compile("ui/app.slint").unwrap();
 
     let re = regex::Regex::new(r#"\{ title: "([^"]+)" \}"#).unwrap();
@@ -18,7 +19,7 @@ fn main() {
             column_names.len(),
             column_names
                 .iter()
-                .map(|title| format!("Column::{}", title.replace(" ", "")))
+                .map(|title| format!("Column::{}", variant_name(title)))
                 .collect::<Vec<_>>()
                 .join(", ")
         );
@@ -39,7 +40,7 @@ pub enum Column {{
 }}",
             column_names
                 .iter()
-                .map(|title| format!("    {},", title.replace(" ", "")))
+                .map(|title| format!("    {},", variant_name(title)))
                 .collect::<Vec<_>>()
                 .join("\n")
         );
@@ -49,3 +50,8 @@ pub enum Column {{
         }
     }
 }
+
+/// The `Column` variant of a title, e.g. `IOPriority` for "I/O Priority".
+fn variant_name(title: &str) -> String {
+    title.chars().filter(char::is_ascii_alphanumeric).collect()
+}
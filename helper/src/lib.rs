@@ -0,0 +1,12 @@
+//! What both the task manager and its privileged helper need to act on a process: the
+//! identity that survives PID reuse, the handle that acts on it and the protocol they
+//! speak. Kept apart from the GUI so the helper started through `pkexec` stays small.
+
+pub mod process_handle;
+pub mod process_identity;
+pub mod process_name;
+pub mod process_tree;
+pub mod protocol;
+pub mod scheduling;
+
+pub type MyProcessID = i32;
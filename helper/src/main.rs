@@ -0,0 +1,41 @@
+//! Started by the task manager through `pkexec` to signal, renice, change the OOM score
+//! adjustment of or truncate a deleted file of a process the user does not own, see
+//! `task_manager_rs_helper::protocol`.
+
+use std::{
+    io::{BufRead, Read},
+    process::ExitCode,
+};
+
+use task_manager_rs_helper::protocol::{HelperRequest, MAX_REQUEST_LENGTH};
+
+fn main() -> ExitCode {
+    // the argument tells which polkit action the user authenticated for
+    let argument = std::env::args().nth(1);
+    let mut request = String::new();
+    let result = std::io::stdin()
+        .lock()
+        .take(MAX_REQUEST_LENGTH as u64 + 1)
+        .read_line(&mut request)
+        .map_err(|e| format!("cannot read the request: {e}"))
+        .and_then(|_| request.parse::<HelperRequest>())
+        .and_then(|request| {
+            if request.argument() == argument.as_deref() {
+                Ok(request)
+            } else {
+                Err("the request does not match the argument of the helper".to_string())
+            }
+        })
+        .and_then(|request| request.execute());
+
+    match result {
+        Ok(()) => {
+            println!("ok");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("error {}", e.replace('\n', " "));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,556 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        unix::fs::MetadataExt,
+    },
+};
+
+use nix::{
+    errno::Errno,
+    sched::{CpuSet, sched_setaffinity},
+    unistd::Pid,
+};
+use procfs::{ProcError, process::Process};
+
+use crate::{
+    MyProcessID,
+    process_identity::{IdentityError, ProcessIdentity},
+    scheduling::{IoPriority, SchedPolicy, set_io_priority, set_sched_policy},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    Identity(IdentityError),
+    Os(Errno),
+    /// A per-thread setting failed on some threads of the process, the others were
+    /// changed. `error` is the first failure.
+    Partial {
+        error: Errno,
+        failed: usize,
+        threads: usize,
+    },
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity(e) => write!(f, "{e}"),
+            Self::Os(e) => write!(f, "{e}"),
+            Self::Partial {
+                error,
+                failed,
+                threads,
+            } => write!(
+                f,
+                "{error} on {failed} of {threads} threads, the other threads were changed"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+impl From<IdentityError> for ActionError {
+    fn from(e: IdentityError) -> Self {
+        Self::Identity(e)
+    }
+}
+
+impl From<Errno> for ActionError {
+    fn from(e: Errno) -> Self {
+        Self::Os(e)
+    }
+}
+
+impl From<ProcError> for ActionError {
+    fn from(e: ProcError) -> Self {
+        match e {
+            ProcError::PermissionDenied(_) => Self::Os(Errno::EACCES),
+            ProcError::NotFound(_) => Self::Identity(IdentityError::Exited),
+            ProcError::Io(e, _) => Self::Os(Errno::from_raw(e.raw_os_error().unwrap_or(libc::EIO))),
+            _ => Self::Os(Errno::EIO),
+        }
+    }
+}
+
+impl From<std::io::Error> for ActionError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Os(Errno::from_raw(e.raw_os_error().unwrap_or(libc::EIO)))
+    }
+}
+
+impl ActionError {
+    /// Whether running the action as root could succeed where it just failed.
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self.errno(), Some(Errno::EPERM | Errno::EACCES))
+    }
+
+    /// The error of the system call that failed, if any did.
+    pub fn errno(&self) -> Option<Errno> {
+        match self {
+            Self::Identity(_) => None,
+            Self::Os(error) | Self::Partial { error, .. } => Some(*error),
+        }
+    }
+}
+
+/// A process chosen by the user for an action.
+///
+/// The pidfd keeps referring to the same process even after it exits, so a signal sent
+/// through it can never reach another process that was given the same PID. Kernels
+/// older than 5.3 have no pidfd, the start time is checked before every signal instead.
+#[derive(Debug)]
+pub struct ProcessHandle {
+    identity: ProcessIdentity,
+    pidfd: Option<OwnedFd>,
+}
+
+impl ProcessHandle {
+    pub fn open(identity: ProcessIdentity) -> Result<Self, ActionError> {
+        let pidfd = match pidfd_open(identity.pid) {
+            Ok(pidfd) => Some(pidfd),
+            Err(Errno::ENOSYS) => None,
+            Err(Errno::ESRCH) => return Err(IdentityError::Exited.into()),
+            Err(e) => return Err(e.into()),
+        };
+
+        // the pidfd refers to whichever process had the PID when it was opened
+        identity.verify()?;
+
+        Ok(Self { identity, pidfd })
+    }
+
+    pub fn identity(&self) -> ProcessIdentity {
+        self.identity
+    }
+
+    /// `signal` is a raw signal number so realtime signals can be sent as well.
+    pub fn send_signal(&self, signal: i32) -> Result<(), ActionError> {
+        match &self.pidfd {
+            Some(pidfd) => pidfd_send_signal(pidfd.as_raw_fd(), signal).map_err(|e| match e {
+                Errno::ESRCH => IdentityError::Exited.into(),
+                e => e.into(),
+            }),
+            None => {
+                self.identity.verify()?;
+                // SAFETY: kill only takes a PID and a signal number
+                Errno::result(unsafe { libc::kill(self.identity.pid, signal) })?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Sends a signal to a single thread, identified by its thread ID and start time.
+    /// `tgkill` fails unless the thread belongs to this process, so a reused thread ID
+    /// can only match a thread started in between within the same process.
+    pub fn send_thread_signal(
+        &self,
+        thread: ProcessIdentity,
+        signal: i32,
+    ) -> Result<(), ActionError> {
+        let process = Process::new(self.identity.pid)?;
+        if process.stat()?.starttime != self.identity.start_ticks {
+            return Err(IdentityError::PidReused.into());
+        }
+        if process.task_from_tid(thread.pid)?.stat()?.starttime != thread.start_ticks {
+            return Err(IdentityError::PidReused.into());
+        }
+        // SAFETY: tgkill only takes a thread group ID, a thread ID and a signal number
+        Errno::result(unsafe {
+            libc::syscall(libc::SYS_tgkill, self.identity.pid, thread.pid, signal)
+        })?;
+        Ok(())
+    }
+
+    /// The nice value belongs to each thread, `setpriority` on the PID alone would only
+    /// renice the main thread, so every thread of the process gets it.
+    pub fn set_nice(&self, nice: i32) -> Result<(), ActionError> {
+        self.apply_to_threads(true, |tid| set_priority(tid, nice))
+    }
+
+    /// Renices a single thread of the process.
+    pub fn set_thread_nice(&self, thread: ProcessIdentity, nice: i32) -> Result<(), ActionError> {
+        self.apply_to_thread(thread, |tid| set_priority(tid, nice))
+    }
+
+    /// Restricts the process to `cpus`. The affinity belongs to each thread like the nice
+    /// value, only the main thread is changed unless `all_threads` is set. Threads started
+    /// afterwards inherit the affinity of the thread starting them.
+    pub fn set_affinity(&self, cpus: &[u32], all_threads: bool) -> Result<(), ActionError> {
+        let cpu_set = cpu_set(cpus)?;
+        self.apply_to_threads(all_threads, |tid| {
+            sched_setaffinity(Pid::from_raw(tid), &cpu_set)
+        })
+    }
+
+    /// Restricts a single thread of the process to `cpus`.
+    pub fn set_thread_affinity(
+        &self,
+        thread: ProcessIdentity,
+        cpus: &[u32],
+    ) -> Result<(), ActionError> {
+        let cpu_set = cpu_set(cpus)?;
+        self.apply_to_thread(thread, |tid| {
+            sched_setaffinity(Pid::from_raw(tid), &cpu_set)
+        })
+    }
+
+    /// Moves every thread of the process to `policy`, it belongs to each thread as well.
+    pub fn set_sched_policy(&self, policy: SchedPolicy) -> Result<(), ActionError> {
+        self.apply_to_threads(true, |tid| set_sched_policy(tid, policy))
+    }
+
+    pub fn set_thread_sched_policy(
+        &self,
+        thread: ProcessIdentity,
+        policy: SchedPolicy,
+    ) -> Result<(), ActionError> {
+        self.apply_to_thread(thread, |tid| set_sched_policy(tid, policy))
+    }
+
+    /// Gives every thread of the process `io_priority`, it belongs to each thread as well.
+    pub fn set_io_priority(&self, io_priority: IoPriority) -> Result<(), ActionError> {
+        self.apply_to_threads(true, |tid| set_io_priority(tid, io_priority))
+    }
+
+    pub fn set_thread_io_priority(
+        &self,
+        thread: ProcessIdentity,
+        io_priority: IoPriority,
+    ) -> Result<(), ActionError> {
+        self.apply_to_thread(thread, |tid| set_io_priority(tid, io_priority))
+    }
+
+    /// Applies a per-thread setting to the main thread, or to every thread of the process.
+    /// A thread that refuses the setting does not keep it from the others, how many
+    /// refused is reported as [`ActionError::Partial`].
+    ///
+    /// Unlike signals there is no pidfd variant of these calls, so the PID could in
+    /// theory be reused between the start time check and the call.
+    fn apply_to_threads(
+        &self,
+        all_threads: bool,
+        apply: impl Fn(MyProcessID) -> Result<(), Errno>,
+    ) -> Result<(), ActionError> {
+        let process = Process::new(self.identity.pid)?;
+        if process.stat()?.starttime != self.identity.start_ticks {
+            return Err(IdentityError::PidReused.into());
+        }
+        if !all_threads {
+            return Ok(apply(self.identity.pid)?);
+        }
+        let mut threads = 0;
+        let mut failures = vec![];
+        for task in process.tasks()?.flatten() {
+            match apply(task.tid) {
+                Ok(()) => threads += 1,
+                // the thread exited since the listing
+                Err(Errno::ESRCH) => {}
+                Err(e) => {
+                    threads += 1;
+                    failures.push(e);
+                }
+            }
+        }
+        match failures[..] {
+            // every thread exited, along with the process
+            [] if threads == 0 => Err(IdentityError::Exited.into()),
+            [] => Ok(()),
+            [error, ..] if failures.len() == threads => Err(error.into()),
+            [error, ..] => Err(ActionError::Partial {
+                error,
+                failed: failures.len(),
+                threads,
+            }),
+        }
+    }
+
+    /// Applies a per-thread setting to a single thread of the process, identified by its
+    /// thread ID and start time as in [`ProcessHandle::send_thread_signal`].
+    fn apply_to_thread(
+        &self,
+        thread: ProcessIdentity,
+        apply: impl FnOnce(MyProcessID) -> Result<(), Errno>,
+    ) -> Result<(), ActionError> {
+        let process = Process::new(self.identity.pid)?;
+        if process.stat()?.starttime != self.identity.start_ticks {
+            return Err(IdentityError::PidReused.into());
+        }
+        if process.task_from_tid(thread.pid)?.stat()?.starttime != thread.start_ticks {
+            return Err(IdentityError::PidReused.into());
+        }
+        apply(thread.pid).map_err(|e| match e {
+            Errno::ESRCH => IdentityError::Exited.into(),
+            e => e.into(),
+        })
+    }
+
+    pub fn set_oom_score_adj(&self, oom_score_adj: i16) -> Result<(), ActionError> {
+        // the opened /proc/<pid> directory keeps referring to this process even if the
+        // PID gets reused, so checking its start time once is enough
+        let process = Process::new(self.identity.pid)?;
+        if process.stat()?.starttime != self.identity.start_ticks {
+            return Err(IdentityError::PidReused.into());
+        }
+        Ok(process.set_oom_score_adj(oom_score_adj)?)
+    }
+
+    /// Frees the blocks of the deleted file open on `fd` without closing it, whatever the
+    /// process writes afterwards starts at its current offset, leaving a hole unless it
+    /// appends. The device and inode numbers make sure the fd was not closed and reused
+    /// for another file since the file was listed.
+    pub fn truncate_file(&self, fd: i32, dev: u64, ino: u64) -> Result<(), ActionError> {
+        self.identity.verify()?;
+        let fd_path = format!("/proc/{}/fd/{fd}", self.identity.pid);
+        let metadata = fs::metadata(&fd_path).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Errno::EBADF.into(),
+            _ => ActionError::from(e),
+        })?;
+        if metadata.dev() != dev || metadata.ino() != ino {
+            return Err(Errno::EBADF.into());
+        }
+        // only the space held by deleted files is given back, a file that can still be
+        // reached by its path (e.g. /etc/shadow held open by a daemon) is never emptied
+        if !metadata.file_type().is_file() || metadata.nlink() != 0 {
+            return Err(Errno::EPERM.into());
+        }
+        OpenOptions::new().write(true).open(&fd_path)?.set_len(0)?;
+        Ok(())
+    }
+}
+
+fn pidfd_open(pid: MyProcessID) -> Result<OwnedFd, Errno> {
+    // SAFETY: pidfd_open takes a PID and flags and returns a new file descriptor
+    let fd = Errno::result(unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) })?;
+    // SAFETY: the file descriptor was just created and is owned by nothing else
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
+
+/// `EINVAL` for an empty set or CPUs beyond what a `cpu_set_t` holds, as
+/// `sched_setaffinity` itself would answer.
+fn cpu_set(cpus: &[u32]) -> Result<CpuSet, Errno> {
+    if cpus.is_empty() {
+        return Err(Errno::EINVAL);
+    }
+    let mut cpu_set = CpuSet::new();
+    for &cpu in cpus {
+        cpu_set.set(cpu as usize)?;
+    }
+    Ok(cpu_set)
+}
+
+fn set_priority(tid: MyProcessID, nice: i32) -> Result<(), Errno> {
+    // SAFETY: setpriority only takes plain integers
+    Errno::result(unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) })
+        .map(drop)
+}
+
+fn pidfd_send_signal(pidfd: RawFd, signal: i32) -> Result<(), Errno> {
+    // SAFETY: a null siginfo makes the kernel fill it in as kill(2) would
+    Errno::result(unsafe {
+        libc::syscall(
+            libc::SYS_pidfd_send_signal,
+            pidfd,
+            signal,
+            std::ptr::null::<libc::siginfo_t>(),
+            0,
+        )
+    })
+    .map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+
+    use super::*;
+
+    fn spawn_sleep() -> (std::process::Child, ProcessIdentity) {
+        let child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .expect("Failed to spawn process");
+        let pid = child.id() as MyProcessID;
+        let start_ticks = Process::new(pid).unwrap().stat().unwrap().starttime;
+        (child, ProcessIdentity { pid, start_ticks })
+    }
+
+    #[test]
+    fn test_send_signal_through_pidfd() {
+        let (mut child, identity) = spawn_sleep();
+
+        let handle = ProcessHandle::open(identity).unwrap();
+        assert_eq!(handle.send_signal(libc::SIGTERM), Ok(()));
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+
+        // the reaped process can no longer be reached, whoever owns the PID now
+        assert_eq!(
+            handle.send_signal(libc::SIGTERM),
+            Err(ActionError::Identity(IdentityError::Exited))
+        );
+    }
+
+    #[test]
+    fn test_send_signal_without_pidfd() {
+        let (mut child, identity) = spawn_sleep();
+
+        let reused = ProcessHandle {
+            identity: ProcessIdentity {
+                start_ticks: identity.start_ticks + 1,
+                ..identity
+            },
+            pidfd: None,
+        };
+        assert_eq!(
+            reused.send_signal(libc::SIGTERM),
+            Err(ActionError::Identity(IdentityError::PidReused))
+        );
+
+        let handle = ProcessHandle {
+            identity,
+            pidfd: None,
+        };
+        assert_eq!(handle.send_signal(libc::SIGTERM), Ok(()));
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn test_send_thread_signal() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let (_keep_alive, stop) = std::sync::mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            // SAFETY: gettid has no arguments
+            sender.send(unsafe { libc::gettid() }).unwrap();
+            let _ = stop.recv();
+        });
+        let tid = receiver.recv().unwrap();
+
+        let process = Process::myself().unwrap();
+        let handle = ProcessHandle::open(ProcessIdentity {
+            pid: process.pid(),
+            start_ticks: process.stat().unwrap().starttime,
+        })
+        .unwrap();
+        let thread = ProcessIdentity {
+            pid: tid,
+            start_ticks: process
+                .task_from_tid(tid)
+                .unwrap()
+                .stat()
+                .unwrap()
+                .starttime,
+        };
+
+        // signal 0 only checks that the thread can be signalled
+        assert_eq!(handle.send_thread_signal(thread, 0), Ok(()));
+        // raising the nice value needs no privileges
+        assert_eq!(handle.set_thread_nice(thread, 1), Ok(()));
+        assert_eq!(process.task_from_tid(tid).unwrap().stat().unwrap().nice, 1);
+
+        // only this thread is pinned, the test runner keeps its other threads
+        let allowed = process.status().unwrap().cpus_allowed_list.unwrap();
+        let cpu = allowed[0].0;
+        assert_eq!(handle.set_thread_affinity(thread, &[cpu]), Ok(()));
+        let pinned = process.task_from_tid(tid).unwrap().status().unwrap();
+        assert_eq!(pinned.cpus_allowed_list, Some(vec![(cpu, cpu)]));
+        assert_eq!(
+            handle.set_thread_affinity(thread, &[]),
+            Err(ActionError::Os(Errno::EINVAL))
+        );
+        assert_eq!(
+            handle.send_thread_signal(
+                ProcessIdentity {
+                    start_ticks: thread.start_ticks + 1,
+                    ..thread
+                },
+                0
+            ),
+            Err(ActionError::Identity(IdentityError::PidReused))
+        );
+    }
+
+    #[test]
+    fn test_set_nice_and_oom_score_adj() {
+        let (mut child, identity) = spawn_sleep();
+
+        let handle = ProcessHandle::open(identity).unwrap();
+        assert_eq!(handle.set_nice(5), Ok(()));
+        assert_eq!(handle.set_oom_score_adj(500), Ok(()));
+
+        let process = Process::new(identity.pid).unwrap();
+        assert_eq!(process.stat().unwrap().nice, 5);
+        assert_eq!(process.oom_score_adj().unwrap(), 500);
+
+        let _ = child.kill();
+        let _ = child.wait();
+        assert_eq!(
+            handle.set_nice(10),
+            Err(ActionError::Identity(IdentityError::Exited))
+        );
+    }
+
+    #[test]
+    fn test_set_affinity() {
+        let (mut child, identity) = spawn_sleep();
+        let process = Process::new(identity.pid).unwrap();
+        let cpu = process.status().unwrap().cpus_allowed_list.unwrap()[0].0;
+
+        let handle = ProcessHandle::open(identity).unwrap();
+        assert_eq!(handle.set_affinity(&[cpu], true), Ok(()));
+        assert_eq!(
+            process.status().unwrap().cpus_allowed_list,
+            Some(vec![(cpu, cpu)])
+        );
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_apply_to_threads_reports_partial_change() {
+        let myself = Process::myself().unwrap();
+        let identity = ProcessIdentity {
+            pid: myself.pid(),
+            start_ticks: myself.stat().unwrap().starttime,
+        };
+        // make sure there is a thread besides the main one
+        let (stop, stopped) = std::sync::mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || stopped.recv());
+
+        let handle = ProcessHandle::open(identity).unwrap();
+        let result = handle.apply_to_threads(true, |tid| {
+            if tid == identity.pid {
+                Err(Errno::EPERM)
+            } else {
+                Ok(())
+            }
+        });
+        assert!(
+            matches!(
+                result,
+                Err(ActionError::Partial {
+                    error: Errno::EPERM,
+                    failed: 1,
+                    threads,
+                }) if threads >= 2
+            ),
+            "Unexpected result: {result:?}"
+        );
+        assert!(result.unwrap_err().is_permission_denied());
+
+        assert_eq!(
+            handle.apply_to_threads(true, |_| Err(Errno::EPERM)),
+            Err(ActionError::Os(Errno::EPERM))
+        );
+        assert_eq!(
+            handle.apply_to_threads(true, |_| Err(Errno::ESRCH)),
+            Err(ActionError::Identity(IdentityError::Exited))
+        );
+
+        stop.send(()).unwrap();
+        let _ = thread.join();
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use procfs::process::Process;
+
+use crate::MyProcessID;
+
+/// A PID alone can be recycled by the kernel as soon as the process exits, the start
+/// time (in clock ticks since boot) tells two processes sharing a PID apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProcessIdentity {
+    pub pid: MyProcessID,
+    pub start_ticks: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentityError {
+    Exited,
+    PidReused,
+}
+
+impl fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exited => write!(f, "the process has exited"),
+            Self::PidReused => write!(f, "the PID now belongs to another process"),
+        }
+    }
+}
+
+impl std::error::Error for IdentityError {}
+
+impl ProcessIdentity {
+    /// Checks that the PID still refers to the process it was taken from.
+    pub fn verify(&self) -> Result<(), IdentityError> {
+        let Ok(stat) = Process::new(self.pid).and_then(|process| process.stat()) else {
+            return Err(IdentityError::Exited);
+        };
+        if stat.starttime != self.start_ticks {
+            return Err(IdentityError::PidReused);
+        }
+        Ok(())
+    }
+
+    /// Whether the process is still alive, a zombie waiting for its parent is not.
+    pub fn is_running(&self) -> bool {
+        Process::new(self.pid)
+            .and_then(|process| process.stat())
+            .is_ok_and(|stat| {
+                stat.starttime == self.start_ticks && !matches!(stat.state, 'Z' | 'X')
+            })
+    }
+}
+
+impl fmt::Display for ProcessIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_process_identity() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .expect("Failed to spawn process");
+        let pid = child.id() as MyProcessID;
+        let start_ticks = Process::new(pid).unwrap().stat().unwrap().starttime;
+
+        let identity = ProcessIdentity { pid, start_ticks };
+        assert_eq!(identity.verify(), Ok(()));
+
+        let reused = ProcessIdentity {
+            pid,
+            start_ticks: start_ticks + 1,
+        };
+        assert_eq!(reused.verify(), Err(IdentityError::PidReused));
+
+        let _ = child.kill();
+        let _ = child.wait();
+        assert_eq!(identity.verify(), Err(IdentityError::Exited));
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+
+use procfs::{
+    ProcResult,
+    process::{ProcState, Process},
+};
+
+use crate::{
+    MyProcessID,
+    process_handle::{ActionError, ProcessHandle},
+    process_identity::ProcessIdentity,
+    process_name::ProcessName,
+};
+
+#[derive(Debug)]
+pub struct ProcessTreeMember {
+    pub name: String,
+    pub handle: ProcessHandle,
+    pub depth: usize,
+}
+
+/// A process and all of its descendants at the time the tree was opened, parents
+/// always come before their children.
+#[derive(Debug)]
+pub struct ProcessTree {
+    members: Vec<ProcessTreeMember>,
+}
+
+impl ProcessTree {
+    /// The tree is empty when the root process itself has exited. The task manager and
+    /// its ancestors are left out, stopping them would leave nobody to resume the tree.
+    pub fn open(root: ProcessIdentity) -> ProcResult<Self> {
+        // children are matched by parent PID, which must still belong to the root
+        if root.verify().is_err() {
+            return Ok(Self { members: vec![] });
+        }
+
+        let mut children = HashMap::<MyProcessID, Vec<(ProcessIdentity, String)>>::new();
+        for process in procfs::process::all_processes()?.flatten() {
+            let Ok(stat) = process.stat() else {
+                continue;
+            };
+            children.entry(stat.ppid).or_default().push((
+                ProcessIdentity {
+                    pid: stat.pid,
+                    start_ticks: stat.starttime,
+                },
+                process.process_name(),
+            ));
+        }
+
+        let excluded = self_and_ancestors();
+        let root_name = Process::new(root.pid)?.process_name();
+        let mut members = vec![];
+        let mut stack = vec![(root, root_name, 0)];
+        while let Some((identity, name, depth)) = stack.pop() {
+            // skip the ones that exited since the scan, their children are still collected
+            let handle = (!excluded.contains(&identity.pid))
+                .then(|| ProcessHandle::open(identity).ok())
+                .flatten();
+            if let Some(handle) = handle {
+                members.push(ProcessTreeMember {
+                    name,
+                    handle,
+                    depth,
+                });
+            }
+            if let Some(children) = children.remove(&identity.pid) {
+                for (child, name) in children.into_iter().rev() {
+                    stack.push((child, name, depth + 1));
+                }
+            }
+        }
+
+        Ok(Self { members })
+    }
+
+    pub fn members(&self) -> &[ProcessTreeMember] {
+        &self.members
+    }
+
+    /// Stops the whole tree so no member can fork or restart the others, delivers the
+    /// signal to the children before their parents, then resumes the members it stopped
+    /// so pending signals like SIGTERM can be handled. The members that were already
+    /// stopped stay so. Returns the members that could not be signalled.
+    pub fn send_signal(&self, signal: i32) -> Vec<(&ProcessTreeMember, ActionError)> {
+        let freeze = signal != libc::SIGSTOP && signal != libc::SIGCONT;
+        let stopped = if freeze {
+            self.members
+                .iter()
+                .filter(|member| !is_stopped(member.handle.identity()))
+                .filter(|member| member.handle.send_signal(libc::SIGSTOP).is_ok())
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let failures = self
+            .members
+            .iter()
+            .rev()
+            .filter_map(|member| member.handle.send_signal(signal).err().map(|e| (member, e)))
+            .collect();
+
+        for member in stopped {
+            let _ = member.handle.send_signal(libc::SIGCONT);
+        }
+
+        failures
+    }
+
+    /// The members that are still running, zombies waiting for their parent excluded.
+    pub fn survivors(&self) -> Vec<&ProcessTreeMember> {
+        self.members
+            .iter()
+            .filter(|member| member.handle.identity().is_running())
+            .collect()
+    }
+}
+
+/// Whether the process is stopped by a signal or a debugger, or has exited.
+fn is_stopped(identity: ProcessIdentity) -> bool {
+    Process::new(identity.pid)
+        .and_then(|process| process.stat())
+        .is_ok_and(|stat| {
+            stat.starttime == identity.start_ticks
+                && matches!(
+                    stat.state(),
+                    Ok(ProcState::Stopped | ProcState::Tracing | ProcState::Zombie)
+                )
+        })
+}
+
+/// The PIDs of the task manager and of every process above it, up to init.
+fn self_and_ancestors() -> HashSet<MyProcessID> {
+    let mut pids = HashSet::new();
+    let mut pid = std::process::id() as MyProcessID;
+    // the parent of init and of kthreadd is 0
+    while pid > 0 && pids.insert(pid) {
+        pid = Process::new(pid)
+            .and_then(|process| process.stat())
+            .map_or(0, |stat| stat.ppid);
+    }
+    pids
+}
+
+/// Whether the tree of the process would include the task manager itself.
+pub fn contains_task_manager(root: ProcessIdentity) -> bool {
+    self_and_ancestors().contains(&root.pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use procfs::process::Process;
+
+    use super::*;
+
+    #[test]
+    fn test_signal_process_tree() {
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg("sleep 10 & sh -c 'sleep 10 & wait' & wait")
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for the tree to spawn
+
+        let pid = child.id() as MyProcessID;
+        let start_ticks = Process::new(pid).unwrap().stat().unwrap().starttime;
+        let tree = ProcessTree::open(ProcessIdentity { pid, start_ticks }).unwrap();
+
+        let depths = tree
+            .members()
+            .iter()
+            .map(|member| member.depth)
+            .collect::<Vec<_>>();
+        assert_eq!(depths.len(), 4, "Unexpected tree: {:?}", tree.members());
+        assert_eq!(depths[0], 0);
+        assert_eq!(depths.iter().filter(|depth| **depth == 1).count(), 2);
+        assert_eq!(depths.iter().filter(|depth| **depth == 2).count(), 1);
+
+        assert!(tree.send_signal(libc::SIGKILL).is_empty());
+        let _ = child.wait();
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for init to reap them
+
+        assert!(tree.survivors().is_empty());
+    }
+
+    #[test]
+    fn test_freeze_keeps_stopped_members_stopped() {
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg("sleep 10 & sleep 10 & wait")
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for the tree to spawn
+
+        let pid = child.id() as MyProcessID;
+        let start_ticks = Process::new(pid).unwrap().stat().unwrap().starttime;
+        let tree = ProcessTree::open(ProcessIdentity { pid, start_ticks }).unwrap();
+        assert_eq!(
+            tree.members().len(),
+            3,
+            "Unexpected tree: {:?}",
+            tree.members()
+        );
+
+        let paused = &tree.members()[1];
+        assert_eq!(paused.handle.send_signal(libc::SIGSTOP), Ok(()));
+        std::thread::sleep(std::time::Duration::from_millis(100)); // Give time for the stop
+
+        // signal 0 only checks that the members can be signalled, the freeze still happens
+        assert!(tree.send_signal(0).is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(100)); // Give time for the resume
+        let states = tree
+            .members()
+            .iter()
+            .map(|member| is_stopped(member.handle.identity()))
+            .collect::<Vec<_>>();
+        assert_eq!(states, [false, true, false]);
+
+        assert!(tree.send_signal(libc::SIGKILL).is_empty());
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_tree_leaves_out_task_manager() {
+        let parent = Process::myself().unwrap().stat().unwrap().ppid;
+        let start_ticks = Process::new(parent).unwrap().stat().unwrap().starttime;
+        let root = ProcessIdentity {
+            pid: parent,
+            start_ticks,
+        };
+        assert!(contains_task_manager(root));
+
+        let tree = ProcessTree::open(root).unwrap();
+        let pids = tree
+            .members()
+            .iter()
+            .map(|member| member.handle.identity().pid)
+            .collect::<Vec<_>>();
+        assert!(!pids.contains(&parent));
+        assert!(!pids.contains(&(std::process::id() as MyProcessID)));
+    }
+}
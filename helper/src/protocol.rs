@@ -0,0 +1,311 @@
+//! The protocol spoken with `task-manager-rs-helper`, the small binary started through
+//! `pkexec` to act on processes of other users.
+//!
+//! The helper reads a single request line from stdin and answers with a single line on
+//! stdout, either `ok` or `error <message>`. A request names the action, the PID and the
+//! start time of the target process, then the argument of the action:
+//!
+//! ```text
+//! signal <pid> <start ticks> <signal number>
+//! signal-tree <pid> <start ticks> <signal number>
+//! renice <pid> <start ticks> <nice>
+//! oom-score-adj <pid> <start ticks> <adjustment>
+//! truncate <pid> <start ticks> <fd>:<device>:<inode>
+//! ```
+//!
+//! Truncating a file is authorized by its own polkit action, which asks for the password
+//! every time. `pkexec` picks that action when the helper is started with
+//! [`TRUNCATE_ARGUMENT`], the helper then only accepts `truncate` requests.
+
+use std::{
+    fmt,
+    io::{Read, Write},
+    process::{Command, Stdio},
+    str::FromStr,
+};
+
+use crate::{
+    MyProcessID,
+    process_handle::ProcessHandle,
+    process_identity::ProcessIdentity,
+    process_tree::{ProcessTree, contains_task_manager},
+};
+
+pub const HELPER_BINARY_NAME: &str = "task-manager-rs-helper";
+
+/// The argument of the helper for `truncate` requests, see the module documentation.
+pub const TRUNCATE_ARGUMENT: &str = "truncate";
+
+/// Requests are tiny, anything longer is rejected before it is parsed.
+pub const MAX_REQUEST_LENGTH: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelperAction {
+    Signal(i32),
+    /// Signals the process and all of its descendants, see [`ProcessTree::send_signal`].
+    SignalTree(i32),
+    Renice(i32),
+    OomScoreAdj(i16),
+    /// Truncates the file open on `fd`, see [`ProcessHandle::truncate_file`].
+    Truncate {
+        fd: i32,
+        dev: u64,
+        ino: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HelperRequest {
+    pub identity: ProcessIdentity,
+    pub action: HelperAction,
+}
+
+impl FromStr for HelperRequest {
+    type Err = String;
+
+    fn from_str(request: &str) -> Result<Self, Self::Err> {
+        if request.len() > MAX_REQUEST_LENGTH {
+            return Err("request too long".to_string());
+        }
+
+        let parts = request.split_ascii_whitespace().collect::<Vec<_>>();
+        let [action, pid, start_ticks, argument] = parts[..] else {
+            return Err("expected <action> <pid> <start ticks> <argument>".to_string());
+        };
+
+        let pid = pid
+            .parse::<MyProcessID>()
+            .map_err(|_| format!("invalid PID: {pid}"))?;
+        // signalling init or the kernel threads' parent is never what the user meant
+        if pid <= 2 {
+            return Err(format!("refusing to act on PID {pid}"));
+        }
+        let start_ticks = start_ticks
+            .parse::<u64>()
+            .map_err(|_| format!("invalid start time: {start_ticks}"))?;
+
+        let action = match action {
+            "signal" | "signal-tree" => match argument.parse::<i32>() {
+                Ok(signal) if (1..=libc::SIGRTMAX()).contains(&signal) => match action {
+                    "signal" => HelperAction::Signal(signal),
+                    _ => HelperAction::SignalTree(signal),
+                },
+                _ => return Err(format!("invalid signal: {argument}")),
+            },
+            "renice" => match argument.parse::<i32>() {
+                Ok(nice) if (-20..=19).contains(&nice) => HelperAction::Renice(nice),
+                _ => return Err(format!("invalid nice value: {argument}")),
+            },
+            "oom-score-adj" => match argument.parse::<i16>() {
+                Ok(adj) if (-1000..=1000).contains(&adj) => HelperAction::OomScoreAdj(adj),
+                _ => return Err(format!("invalid OOM score adjustment: {argument}")),
+            },
+            "truncate" => match argument.split(':').collect::<Vec<_>>()[..] {
+                [fd, dev, ino] => match (fd.parse::<i32>(), dev.parse(), ino.parse()) {
+                    (Ok(fd), Ok(dev), Ok(ino)) if fd >= 0 => {
+                        HelperAction::Truncate { fd, dev, ino }
+                    }
+                    _ => return Err(format!("invalid file: {argument}")),
+                },
+                _ => return Err(format!("invalid file: {argument}")),
+            },
+            _ => return Err(format!("unknown action: {action}")),
+        };
+
+        Ok(Self {
+            identity: ProcessIdentity { pid, start_ticks },
+            action,
+        })
+    }
+}
+
+impl fmt::Display for HelperRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (action, argument) = match self.action {
+            HelperAction::Signal(signal) => ("signal", signal.to_string()),
+            HelperAction::SignalTree(signal) => ("signal-tree", signal.to_string()),
+            HelperAction::Renice(nice) => ("renice", nice.to_string()),
+            HelperAction::OomScoreAdj(adj) => ("oom-score-adj", adj.to_string()),
+            HelperAction::Truncate { fd, dev, ino } => ("truncate", format!("{fd}:{dev}:{ino}")),
+        };
+        write!(
+            f,
+            "{action} {} {} {argument}",
+            self.identity.pid, self.identity.start_ticks
+        )
+    }
+}
+
+impl HelperRequest {
+    /// The argument the helper has to be started with for this request.
+    pub fn argument(&self) -> Option<&'static str> {
+        match self.action {
+            HelperAction::Truncate { .. } => Some(TRUNCATE_ARGUMENT),
+            _ => None,
+        }
+    }
+
+    /// Performs the request with the privileges of the current process, this is what
+    /// the helper does after reading the request.
+    pub fn execute(&self) -> Result<(), String> {
+        let handle = || ProcessHandle::open(self.identity).map_err(|e| e.to_string());
+        match self.action {
+            HelperAction::Signal(signal) => handle()?.send_signal(signal),
+            HelperAction::SignalTree(signal) => return self.signal_tree(signal),
+            HelperAction::Renice(nice) => handle()?.set_nice(nice),
+            HelperAction::OomScoreAdj(adj) => handle()?.set_oom_score_adj(adj),
+            HelperAction::Truncate { fd, dev, ino } => handle()?.truncate_file(fd, dev, ino),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// The tree is collected again with the privileges of the helper, the helper and
+    /// whoever started it are left out as they are by the task manager.
+    fn signal_tree(&self, signal: i32) -> Result<(), String> {
+        self.identity.verify().map_err(|e| e.to_string())?;
+        if contains_task_manager(self.identity) {
+            return Err("the tree contains the task manager".to_string());
+        }
+        let tree = ProcessTree::open(self.identity).map_err(|e| e.to_string())?;
+
+        let failures = tree
+            .send_signal(signal)
+            .into_iter()
+            .map(|(member, e)| format!("{} ({}): {e}", member.name, member.handle.identity()))
+            .collect::<Vec<_>>();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "failed to signal {} of {} processes: {}",
+                failures.len(),
+                tree.members().len(),
+                failures.join(", ")
+            ))
+        }
+    }
+
+    /// Runs the helper through `pkexec`, which asks the user to authenticate as an
+    /// administrator according to the polkit policy shipped with the helper.
+    pub fn execute_as_administrator(&self) -> Result<(), String> {
+        let helper = std::env::current_exe()
+            .map_err(|e| format!("cannot locate the helper: {e}"))?
+            .with_file_name(HELPER_BINARY_NAME);
+
+        let mut child = Command::new("pkexec")
+            .arg(&helper)
+            .args(self.argument())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("cannot run pkexec: {e}"))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{self}").map_err(|e| format!("cannot send the request: {e}"))?;
+        }
+
+        let mut response = String::new();
+        if let Some(mut stdout) = child.stdout.take() {
+            stdout
+                .read_to_string(&mut response)
+                .map_err(|e| format!("cannot read the response: {e}"))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("cannot wait for the helper: {e}"))?;
+
+        // pkexec exits with 126 when the authentication was dismissed, 127 when it failed
+        match (status.code(), response.trim()) {
+            (_, "ok") => Ok(()),
+            (_, response) if response.starts_with("error ") => {
+                Err(response.trim_start_matches("error ").to_string())
+            }
+            (Some(126), _) => Err("authentication was dismissed".to_string()),
+            (Some(127), _) => Err("not authorized".to_string()),
+            _ => Err(format!("the helper failed ({status})")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_helper_request() {
+        let request = "signal 1234 5678 15".parse::<HelperRequest>().unwrap();
+        assert_eq!(
+            request,
+            HelperRequest {
+                identity: ProcessIdentity {
+                    pid: 1234,
+                    start_ticks: 5678
+                },
+                action: HelperAction::Signal(15),
+            }
+        );
+        assert_eq!(request.to_string(), "signal 1234 5678 15");
+
+        let request = "signal-tree 1234 5678 9".parse::<HelperRequest>().unwrap();
+        assert_eq!(request.action, HelperAction::SignalTree(9));
+        assert_eq!(request.to_string(), "signal-tree 1234 5678 9");
+
+        assert_eq!(
+            " renice 42 7 -5\n".parse::<HelperRequest>().unwrap().action,
+            HelperAction::Renice(-5)
+        );
+        assert_eq!(
+            "oom-score-adj 42 7 -1000"
+                .parse::<HelperRequest>()
+                .unwrap()
+                .action,
+            HelperAction::OomScoreAdj(-1000)
+        );
+
+        let request = format!(
+            "truncate 4194304 {} 1048576:{}:{}",
+            u64::MAX,
+            u64::MAX,
+            u64::MAX
+        );
+        assert!(request.len() <= MAX_REQUEST_LENGTH);
+        let parsed = request.parse::<HelperRequest>().unwrap();
+        assert_eq!(
+            parsed.action,
+            HelperAction::Truncate {
+                fd: 1048576,
+                dev: u64::MAX,
+                ino: u64::MAX
+            }
+        );
+        assert_eq!(parsed.to_string(), request);
+    }
+
+    #[test]
+    fn test_reject_invalid_helper_request() {
+        for request in [
+            "",
+            "signal 1234 5678",
+            "signal 1234 5678 15 extra",
+            "signal 1 5678 15",
+            "signal -1 5678 15",
+            "signal 1234 5678 0",
+            "signal 1234 5678 65",
+            "signal-tree 1234 5678 0",
+            "signal 1234 -1 15",
+            "renice 1234 5678 20",
+            "oom-score-adj 1234 5678 1001",
+            "truncate 1234 5678 5",
+            "truncate 1234 5678 -1:2049:12",
+            "truncate 1234 5678 5:2049:12:0",
+            "chmod 1234 5678 777",
+            &format!("signal 1234 5678 15{}", " ".repeat(MAX_REQUEST_LENGTH)),
+        ] {
+            assert!(
+                request.parse::<HelperRequest>().is_err(),
+                "Request should be rejected: {request:?}"
+            );
+        }
+    }
+}
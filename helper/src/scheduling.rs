@@ -0,0 +1,243 @@
+use std::fmt;
+
+use nix::errno::Errno;
+
+use crate::MyProcessID;
+
+const SCHED_DEADLINE: u32 = 6;
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+/// The bits between the level and the class carry hints since Linux 6.4.
+const IOPRIO_LEVEL_MASK: libc::c_int = 0x7;
+
+/// The CPU scheduling policy of a thread, see sched(7). Declared from the least to the
+/// most urgent, so sorting by it puts the background work first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchedPolicy {
+    Idle,
+    Batch,
+    Other,
+    /// The real-time policies carry their priority, from 1 to 99.
+    RoundRobin(u32),
+    Fifo(u32),
+    Deadline,
+    Unknown(u32),
+}
+
+impl SchedPolicy {
+    /// The policy and real-time priority fields of `/proc/<pid>/stat`.
+    pub fn from_stat(policy: u32, rt_priority: u32) -> Self {
+        match policy as libc::c_int {
+            libc::SCHED_OTHER => Self::Other,
+            libc::SCHED_FIFO => Self::Fifo(rt_priority),
+            libc::SCHED_RR => Self::RoundRobin(rt_priority),
+            libc::SCHED_BATCH => Self::Batch,
+            libc::SCHED_IDLE => Self::Idle,
+            _ if policy == SCHED_DEADLINE => Self::Deadline,
+            _ => Self::Unknown(policy),
+        }
+    }
+
+    /// Only the policies `sched_setscheduler` can set are accepted, `SCHED_DEADLINE`
+    /// needs a runtime, deadline and period.
+    pub fn parse(name: &str, rt_priority: u32) -> Option<Self> {
+        match name {
+            "SCHED_OTHER" => Some(Self::Other),
+            "SCHED_BATCH" => Some(Self::Batch),
+            "SCHED_IDLE" => Some(Self::Idle),
+            "SCHED_FIFO" => Some(Self::Fifo(rt_priority)),
+            "SCHED_RR" => Some(Self::RoundRobin(rt_priority)),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Other => "SCHED_OTHER",
+            Self::Batch => "SCHED_BATCH",
+            Self::Idle => "SCHED_IDLE",
+            Self::Fifo(_) => "SCHED_FIFO",
+            Self::RoundRobin(_) => "SCHED_RR",
+            Self::Deadline => "SCHED_DEADLINE",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+
+    pub fn rt_priority(&self) -> Option<u32> {
+        match self {
+            Self::Fifo(priority) | Self::RoundRobin(priority) => Some(*priority),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SchedPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fifo(priority) | Self::RoundRobin(priority) => {
+                write!(f, "{} ({priority})", self.name())
+            }
+            Self::Unknown(policy) => write!(f, "unknown ({policy})"),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+/// The I/O scheduling class and level of a thread, see ioprio_set(2). Lower levels are
+/// served first within a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IoPriority {
+    /// Never set, the best-effort level then follows the nice value.
+    None,
+    Idle,
+    BestEffort(u8),
+    RealTime(u8),
+}
+
+impl IoPriority {
+    fn from_raw(ioprio: libc::c_int) -> Option<Self> {
+        let level = (ioprio & IOPRIO_LEVEL_MASK) as u8;
+        match ioprio >> IOPRIO_CLASS_SHIFT {
+            0 => Some(Self::None),
+            1 => Some(Self::RealTime(level)),
+            2 => Some(Self::BestEffort(level)),
+            3 => Some(Self::Idle),
+            _ => None,
+        }
+    }
+
+    fn to_raw(self) -> libc::c_int {
+        let (class, level) = match self {
+            Self::None => (0, 0),
+            Self::RealTime(level) => (1, level),
+            Self::BestEffort(level) => (2, level),
+            Self::Idle => (3, 0),
+        };
+        (class << IOPRIO_CLASS_SHIFT) | libc::c_int::from(level)
+    }
+
+    /// The class as `ionice` names it, the level is ignored by the classes without one.
+    pub fn parse(class: &str, level: u8) -> Option<Self> {
+        let level = level.min(7);
+        match class {
+            "none" => Some(Self::None),
+            "realtime" => Some(Self::RealTime(level)),
+            "best-effort" => Some(Self::BestEffort(level)),
+            "idle" => Some(Self::Idle),
+            _ => None,
+        }
+    }
+
+    pub fn class_name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::RealTime(_) => "realtime",
+            Self::BestEffort(_) => "best-effort",
+            Self::Idle => "idle",
+        }
+    }
+
+    pub fn level(&self) -> Option<u8> {
+        match self {
+            Self::RealTime(level) | Self::BestEffort(level) => Some(*level),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for IoPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.level() {
+            Some(level) => write!(f, "{} {level}", self.class_name()),
+            None => write!(f, "{}", self.class_name()),
+        }
+    }
+}
+
+/// The I/O priority of a single thread, anyone may read it.
+pub fn io_priority_of(tid: MyProcessID) -> Result<IoPriority, Errno> {
+    // SAFETY: ioprio_get only takes plain integers
+    let ioprio =
+        Errno::result(unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, tid) })?;
+    IoPriority::from_raw(ioprio as libc::c_int).ok_or(Errno::EINVAL)
+}
+
+pub fn set_io_priority(tid: MyProcessID, io_priority: IoPriority) -> Result<(), Errno> {
+    // SAFETY: ioprio_set only takes plain integers
+    Errno::result(unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            tid,
+            io_priority.to_raw(),
+        )
+    })
+    .map(drop)
+}
+
+/// Leaving a real-time policy resets the priority to 0 as the other policies require.
+pub fn set_sched_policy(tid: MyProcessID, policy: SchedPolicy) -> Result<(), Errno> {
+    let (policy, priority) = match policy {
+        SchedPolicy::Other => (libc::SCHED_OTHER, 0),
+        SchedPolicy::Batch => (libc::SCHED_BATCH, 0),
+        SchedPolicy::Idle => (libc::SCHED_IDLE, 0),
+        SchedPolicy::Fifo(priority) => (libc::SCHED_FIFO, priority),
+        SchedPolicy::RoundRobin(priority) => (libc::SCHED_RR, priority),
+        SchedPolicy::Deadline | SchedPolicy::Unknown(_) => return Err(Errno::EINVAL),
+    };
+    let param = libc::sched_param {
+        sched_priority: priority as libc::c_int,
+    };
+    // SAFETY: the parameters are read during the call only
+    Errno::result(unsafe { libc::sched_setscheduler(tid, policy, &param) }).map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduling_of_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .expect("Failed to spawn process");
+        let pid = child.id() as MyProcessID;
+
+        // moving to SCHED_IDLE and the idle I/O class needs no privileges
+        assert_eq!(set_sched_policy(pid, SchedPolicy::Idle), Ok(()));
+        assert_eq!(set_io_priority(pid, IoPriority::Idle), Ok(()));
+        assert_eq!(set_io_priority(pid, IoPriority::BestEffort(7)), Ok(()));
+
+        let stat = procfs::process::Process::new(pid).unwrap().stat().unwrap();
+        assert_eq!(
+            SchedPolicy::from_stat(stat.policy.unwrap(), stat.rt_priority.unwrap()),
+            SchedPolicy::Idle
+        );
+        assert_eq!(io_priority_of(pid), Ok(IoPriority::BestEffort(7)));
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_parse_scheduling() {
+        assert_eq!(
+            SchedPolicy::parse("SCHED_FIFO", 50),
+            Some(SchedPolicy::Fifo(50))
+        );
+        assert_eq!(SchedPolicy::parse("SCHED_DEADLINE", 0), None);
+        assert_eq!(SchedPolicy::Fifo(50).to_string(), "SCHED_FIFO (50)");
+        assert_eq!(SchedPolicy::from_stat(6, 0), SchedPolicy::Deadline);
+
+        assert_eq!(
+            IoPriority::parse("best-effort", 4),
+            Some(IoPriority::BestEffort(4))
+        );
+        assert_eq!(IoPriority::parse("idle", 4), Some(IoPriority::Idle));
+        assert_eq!(IoPriority::BestEffort(4).to_string(), "best-effort 4");
+        let raw = IoPriority::RealTime(3).to_raw();
+        assert_eq!(IoPriority::from_raw(raw), Some(IoPriority::RealTime(3)));
+    }
+}
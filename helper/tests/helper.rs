@@ -0,0 +1,163 @@
+use std::{
+    io::Write,
+    os::unix::{fs::MetadataExt, process::ExitStatusExt},
+    process::{Command, Stdio},
+};
+
+use procfs::process::{Process, Stat};
+
+fn run_helper(request: &str) -> (bool, String) {
+    run_helper_with(&[], request)
+}
+
+fn run_helper_with(args: &[&str], request: &str) -> (bool, String) {
+    let mut helper = Command::new(env!("CARGO_BIN_EXE_task-manager-rs-helper"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn the helper");
+    writeln!(helper.stdin.take().unwrap(), "{request}").unwrap();
+    let output = helper.wait_with_output().unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    )
+}
+
+fn stat(pid: u32) -> Stat {
+    Process::new(pid as i32).unwrap().stat().unwrap()
+}
+
+#[test]
+fn test_helper_signals_process() {
+    let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    let start_ticks = stat(child.id()).starttime;
+
+    let (success, response) = run_helper(&format!("signal {} {} 15", child.id(), start_ticks + 1));
+    assert!(!success);
+    assert_eq!(response, "error the PID now belongs to another process");
+
+    let (success, response) = run_helper(&format!("signal {} {start_ticks} 15", child.id()));
+    assert!(success, "Unexpected response: {response}");
+    assert_eq!(response, "ok");
+    assert_eq!(child.wait().unwrap().signal(), Some(15));
+}
+
+#[test]
+fn test_helper_signals_tree() {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg("sleep 10 & wait")
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for the tree to spawn
+    let start_ticks = stat(child.id()).starttime;
+
+    let (success, response) = run_helper(&format!("signal-tree {} {start_ticks} 9", child.id()));
+    assert!(success, "Unexpected response: {response}");
+    assert_eq!(child.wait().unwrap().signal(), Some(9));
+}
+
+#[test]
+fn test_helper_renices_process() {
+    let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    let start_ticks = stat(child.id()).starttime;
+
+    let (success, response) = run_helper(&format!("renice {} {start_ticks} 7", child.id()));
+    assert!(success, "Unexpected response: {response}");
+    assert_eq!(stat(child.id()).nice, 7);
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn test_helper_truncates_deleted_file() {
+    let file = std::env::temp_dir().join(format!("helper-truncate-{}.log", std::process::id()));
+    std::fs::write(&file, vec![b'x'; 4096]).unwrap();
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(format!("exec 5< {}; exec sleep 10", file.display()))
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for process to open fd
+    let metadata = std::fs::metadata(&file).unwrap();
+    let start_ticks = stat(child.id()).starttime;
+    let fd_path = format!("/proc/{}/fd/5", child.id());
+
+    // the truncate action of polkit is the only one allowed to truncate
+    let request = format!(
+        "truncate {} {start_ticks} 5:{}:{}",
+        child.id(),
+        metadata.dev(),
+        metadata.ino()
+    );
+    let (success, response) = run_helper(&request);
+    assert!(!success);
+    assert_eq!(
+        response,
+        "error the request does not match the argument of the helper"
+    );
+    let (success, _) = run_helper_with(&["truncate"], "signal 1234 5678 15");
+    assert!(!success);
+
+    // a file still linked somewhere is not a deleted file
+    let (success, response) = run_helper_with(
+        &["truncate"],
+        &format!(
+            "truncate {} {start_ticks} 5:{}:{}",
+            child.id(),
+            metadata.dev(),
+            metadata.ino()
+        ),
+    );
+    assert!(!success);
+    assert_eq!(response, "error EPERM: Operation not permitted");
+    assert_eq!(std::fs::metadata(&file).unwrap().len(), 4096);
+
+    std::fs::remove_file(&file).unwrap();
+
+    // another file on the same fd is left alone
+    let (success, response) = run_helper_with(
+        &["truncate"],
+        &format!(
+            "truncate {} {start_ticks} 5:{}:{}",
+            child.id(),
+            metadata.dev(),
+            metadata.ino() + 1
+        ),
+    );
+    assert!(!success);
+    assert_eq!(response, "error EBADF: Bad file number");
+    assert_eq!(std::fs::metadata(&fd_path).unwrap().len(), 4096);
+
+    let (success, response) = run_helper_with(
+        &["truncate"],
+        &format!(
+            "truncate {} {start_ticks} 5:{}:{}",
+            child.id(),
+            metadata.dev(),
+            metadata.ino()
+        ),
+    );
+    assert!(success, "Unexpected response: {response}");
+    assert_eq!(std::fs::metadata(&fd_path).unwrap().len(), 0);
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn test_helper_rejects_invalid_request() {
+    let (success, response) = run_helper("signal 1 1 9");
+    assert!(!success);
+    assert_eq!(response, "error refusing to act on PID 1");
+
+    let (success, response) = run_helper("rm -rf /");
+    assert!(!success);
+    assert!(
+        response.starts_with("error "),
+        "Unexpected response: {response}"
+    );
+}
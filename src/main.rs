@@ -1,28 +1,124 @@
-mod manager;
-mod utils;
-
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
-use nix::{sys::signal, unistd::Pid};
-use slint::{ComponentHandle, SharedString};
+use nix::errno::Errno;
+use procfs::process::Process;
+use slint::{ComponentHandle, Model, ModelRc, SharedString, StandardListViewItem, VecModel};
 use tokio::{select, sync::mpsc, time::Duration};
 use tracing::error;
 
-use crate::manager::{
-    Column, MyProcess, SortOrder, ToStandardListViewItems,
-    get_sorted_process_list::get_sorted_process_list,
+use task_manager_rs_helper::protocol::{HelperAction, HelperRequest};
+
+use task_manager_rs::{
+    manager::{
+        Column, MyProcess, MyProcessID, RowIdentity, SortOrder, ToStandardListViewItems,
+        credentials::credentials_of,
+        deleted_files::{self, DeletedFile, DeletedFileKey},
+        environment::{self, environment_of},
+        fd_table::FdTable,
+        get_sorted_process_list::get_sorted_process_list,
+        handles::{Handle, handles_of},
+        memory_maps::{MappingGroup, MemoryMapping, group_by_backing, memory_maps_of},
+        memory_usage::MemoryMetric,
+        outdated_files::{OutdatedGroup, group_by_file},
+        process_fate::ProcessFate,
+        process_handle::{ActionError, ProcessHandle},
+        process_identity::{IdentityError, ProcessIdentity},
+        process_table_model::ProcessTableModel,
+        process_tree::{ProcessTree, contains_task_manager},
+        scan_state::ScanState,
+        scheduling::{IoPriority, SchedPolicy},
+        sockets::{self, Connection},
+    },
+    utils::{
+        cpu_list::{cpu_ranges, format_cpu_list, online_cpus},
+        human_readable_byte::human_readable_byte,
+        parse_signal::{is_thread_signal, parse_signal, signal_name},
+    },
 };
 
 include!(concat!(env!("OUT_DIR"), "/columns_order.rs"));
 slint::include_modules!();
 
+/// How long a SIGKILLed process gets to disappear before its fate is reported.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
 #[derive(Debug, Default)]
 struct BackendAppState {
     search_term: RwLock<String>,
+    memory_metric: RwLock<MemoryMetric>,
     sort_order: RwLock<SortOrder>,
     sort_by: RwLock<Column>,
     curr_proc_list: RwLock<Vec<(MyProcess, usize)>>,
-    request_terminate_proc: RwLock<Option<(String, i32)>>,
+    selected_proc: RwLock<Option<(String, RowIdentity)>>,
+    expanded_procs: RwLock<HashSet<ProcessIdentity>>,
+    request_signal_proc: RwLock<Option<(String, ProcessHandle, i32)>>,
+    request_signal_thread: RwLock<Option<(String, ProcessHandle, ProcessIdentity, i32)>>,
+    /// The name and identity of the root along with its tree.
+    request_signal_tree: RwLock<Option<(String, ProcessIdentity, ProcessTree, i32)>>,
+    force_kill_proc: RwLock<Option<(String, ProcessHandle)>>,
+    admin_retry: RwLock<Option<(String, HelperRequest)>>,
+    scan_state: RwLock<ScanState>,
+    /// The indices in `COLUMN_TITLES` of the columns shown, in their order.
+    visible_columns: RwLock<Vec<usize>>,
+    /// Makes the next scan check every process for outdated files again.
+    recheck_outdated_files: RwLock<bool>,
+    details_open: RwLock<bool>,
+    details_tab: RwLock<DetailsTab>,
+    /// Set to cancel the running refresh of the details pane.
+    details_refresh: RwLock<Arc<AtomicBool>>,
+    deleted_files: RwLock<Vec<DeletedFile>>,
+    selected_deleted_file: RwLock<Option<DeletedFileKey>>,
+    request_truncate: RwLock<Option<DeletedFile>>,
+    /// The process to pin, and the thread when only one thread of it is.
+    request_affinity: RwLock<Option<(String, ProcessHandle, Option<ProcessIdentity>)>>,
+    request_scheduling: RwLock<Option<SchedulingRequest>>,
+}
+
+/// The tabs of the details pane, in the order of `details.slint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum DetailsTab {
+    #[default]
+    Handles,
+    MemoryMaps,
+    Environment,
+    Credentials,
+    Connections,
+    DeletedFiles,
+    Outdated,
+}
+
+impl DetailsTab {
+    fn from_index(index: i32) -> Option<Self> {
+        [
+            Self::Handles,
+            Self::MemoryMaps,
+            Self::Environment,
+            Self::Credentials,
+            Self::Connections,
+            Self::DeletedFiles,
+            Self::Outdated,
+        ]
+        .get(usize::try_from(index).ok()?)
+        .copied()
+    }
+}
+
+/// The scheduling of a process or thread as the dialog opened with, only what the user
+/// changed in it is applied.
+#[derive(Debug)]
+struct SchedulingRequest {
+    description: String,
+    handle: ProcessHandle,
+    thread: Option<ProcessIdentity>,
+    /// What the dialog was filled with, the defaults when the actual value is unknown.
+    sched_policy: SchedPolicy,
+    io_priority: IoPriority,
 }
 
 #[tokio::main]
@@ -32,13 +128,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
     let ui = AppWindow::new().expect("Failed to create UI");
 
-    let backend_state = Arc::new(BackendAppState::default());
+    let backend_state = Arc::new(BackendAppState {
+        visible_columns: RwLock::new(default_visible_columns()),
+        ..Default::default()
+    });
     let (f5_req_send, mut f5_req_recv) = mpsc::channel::<()>(1);
 
     let backend_state_clone = backend_state.clone();
     let f5_req_send_clone = f5_req_send.clone();
     ui.on_sort_ascending(move |sort_by| {
-        let Some(sort_by_column) = COLUMN_TITLES.get(sort_by as usize) else {
+        let Some(sort_by_column) = visible_column(&backend_state_clone, sort_by) else {
             error!("Invalid sort column index: {sort_by}");
             return;
         };
@@ -52,7 +151,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 return;
             };
             sort_order.clone_from(&SortOrder::Ascending);
-            sort_by.clone_from(sort_by_column);
+            *sort_by = sort_by_column;
         }
         if let Err(e) = f5_req_send_clone.try_send(()) {
             error!("Failed to send F5 request: {e}");
@@ -62,8 +161,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let f5_req_send_clone = f5_req_send.clone();
     let backend_state_clone = backend_state.clone();
     ui.on_sort_descending(move |sort_by| {
-        #[allow(clippy::cast_sign_loss)]
-        let Some(sort_by_column) = COLUMN_TITLES.get(sort_by as usize) else {
+        let Some(sort_by_column) = visible_column(&backend_state_clone, sort_by) else {
             error!("Invalid sort column index: {sort_by}");
             return;
         };
@@ -77,7 +175,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 return;
             };
             sort_order.clone_from(&SortOrder::Descending);
-            sort_by.clone_from(sort_by_column);
+            *sort_by = sort_by_column;
         }
         if let Err(e) = f5_req_send_clone.try_send(()) {
             error!("Failed to send F5 request: {e}");
@@ -116,114 +214,1857 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    let f5_req_send_clone = f5_req_send.clone();
+    let backend_state_clone = backend_state.clone();
+    ui.on_memory_metric_changed(move |metric| {
+        let Ok(metric) = metric.parse::<MemoryMetric>() else {
+            error!("Invalid memory metric: {metric}");
+            return;
+        };
+        {
+            let Ok(mut memory_metric) = backend_state_clone.memory_metric.write() else {
+                error!("Failed to get write lock on memory metric");
+                return;
+            };
+            *memory_metric = metric;
+        }
+        if let Err(e) = f5_req_send_clone.try_send(()) {
+            error!("Failed to send F5 request: {e}");
+        }
+    });
+
     let ui_handle = ui.as_weak();
     let backend_state_clone = backend_state.clone();
-    ui.on_request_terminate_process(move |proc_idx| {
+    ui.on_select_process(move |row| {
         let Some(app_window) = ui_handle.upgrade() else {
             error!("Failed to upgrade UI handle");
             return;
         };
 
-        let target_proc = {
+        let selected_proc = {
             let Ok(proc_list) = backend_state_clone.curr_proc_list.read() else {
                 error!("Failed to get read lock on current process list");
                 return;
             };
-            proc_list
-                .get(proc_idx as usize)
-                .map(|(proc, _)| (proc.name.clone(), proc.id))
+            usize::try_from(row)
+                .ok()
+                .and_then(|row| proc_list.get(row))
+                .map(|(proc, _)| ((proc.name.clone(), proc.row_identity()), proc.nice))
+        };
+        let (selected_proc, nice) = selected_proc.unzip();
+
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_has_selected_process(selected_proc.is_some());
+        if let Some(nice) = nice {
+            app_state.set_nice_value(nice as i32);
+        }
+        // the adjustment is shared by all the threads of a process
+        if let Some((_, row)) = &selected_proc
+            && let Ok(adj) = Process::new(row.thread_of.unwrap_or(row.identity.pid))
+                .and_then(|process| process.oom_score_adj())
+        {
+            app_state.set_oom_score_adj(i32::from(adj));
+        }
+
+        {
+            let Ok(mut selected) = backend_state_clone.selected_proc.write() else {
+                error!("Failed to get write lock on selected process");
+                return;
+            };
+            *selected = selected_proc;
+        }
+        refresh_details(ui_handle.clone(), backend_state_clone.clone());
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_details_toggled(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        {
+            let Ok(mut details_open) = backend_state_clone.details_open.write() else {
+                error!("Failed to get write lock on details open");
+                return;
+            };
+            *details_open = AppWindowState::get(&app_window).get_details_open();
+        }
+        refresh_details(ui_handle.clone(), backend_state_clone.clone());
+    });
+
+    let ui_handle = ui.as_weak();
+    let f5_req_send_clone = f5_req_send.clone();
+    let backend_state_clone = backend_state.clone();
+    ui.on_details_tab_changed(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let index = AppWindowState::get(&app_window).get_details_tab();
+        let Some(tab) = DetailsTab::from_index(index) else {
+            error!("Invalid details tab index: {index}");
+            return;
         };
+        {
+            let Ok(mut details_tab) = backend_state_clone.details_tab.write() else {
+                error!("Failed to get write lock on details tab");
+                return;
+            };
+            *details_tab = tab;
+        }
+        // opening the tab is how the user asks whether an upgrade left processes behind
+        if tab == DetailsTab::Outdated {
+            match backend_state_clone.recheck_outdated_files.write() {
+                Ok(mut recheck) => *recheck = true,
+                Err(_) => error!("Failed to get write lock on recheck outdated files"),
+            }
+            let _ = f5_req_send_clone.try_send(());
+        }
+        refresh_details(ui_handle.clone(), backend_state_clone.clone());
+    });
 
+    let f5_req_send_clone = f5_req_send.clone();
+    let backend_state_clone = backend_state.clone();
+    ui.on_toggle_threads(move || {
+        let Some(row) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return;
+            };
+            selected_proc.as_ref().map(|(_, row)| *row)
+        }) else {
+            return;
+        };
+        // on a thread row, collapse the process it belongs to
+        let identity = match row.thread_of {
+            Some(process_id) => {
+                let Some(identity) = process_identity_of(&backend_state_clone, process_id) else {
+                    return;
+                };
+                identity
+            }
+            None => row.identity,
+        };
         {
-            let Ok(mut request_terminate_proc) = backend_state_clone.request_terminate_proc.write()
+            let Ok(mut expanded_procs) = backend_state_clone.expanded_procs.write() else {
+                error!("Failed to get write lock on expanded processes");
+                return;
+            };
+            // forget the processes that have exited in the meantime
+            expanded_procs.retain(|identity| identity.is_running());
+            if !expanded_procs.remove(&identity) {
+                expanded_procs.insert(identity);
+            }
+        }
+        if let Err(e) = f5_req_send_clone.try_send(()) {
+            error!("Failed to send F5 request: {e}");
+        }
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_request_signal_process(move |signal| {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return false;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some(signal) = parse_signal(&signal) else {
+            app_state.set_status_message(SharedString::from(format!(
+                "Unknown signal: \"{}\"",
+                signal.trim()
+            )));
+            return false;
+        };
+
+        let Some((name, row)) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return false;
+            };
+            selected_proc.clone()
+        }) else {
+            return false;
+        };
+        if let Some(process_id) = row.thread_of {
+            return request_signal_thread(
+                &backend_state_clone,
+                &app_state,
+                (name, row.identity, process_id),
+                signal,
+            );
+        }
+        request_signal_process(
+            &backend_state_clone,
+            &app_state,
+            (name, row.identity),
+            signal,
+        )
+    });
+
+    let backend_state_clone = backend_state.clone();
+    let ui_handle = ui.as_weak();
+    ui.on_confirm_signal_process(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let thread_request = {
+            let Ok(mut request_signal_thread) = backend_state_clone.request_signal_thread.write()
+            else {
+                error!("Failed to get write lock on request signal thread");
+                return;
+            };
+            request_signal_thread.take()
+        };
+        if let Some((name, handle, thread, signal)) = thread_request {
+            let signal_name = signal_name(signal);
+            let description = format!("{name} (thread {thread} of {})", handle.identity());
+            app_state.set_admin_retry_action(SharedString::from(""));
+            app_state.set_to_be_signalled_process(SharedString::from(""));
+            app_state.set_status_message(SharedString::from(
+                match handle.send_thread_signal(thread, signal) {
+                    Ok(()) => format!("Sent {signal_name} to {description}"),
+                    Err(e) => {
+                        error!("Failed to send {signal_name} to thread: {e}");
+                        format!("Failed to send {signal_name} to {description}: {e}")
+                    }
+                },
+            ));
+            return;
+        }
+
+        let Some((name, handle, signal)) = ({
+            let Ok(mut request_signal_proc) = backend_state_clone.request_signal_proc.write()
             else {
-                error!("Failed to get write lock on request terminate process");
+                error!("Failed to get write lock on request signal process");
                 return;
             };
-            *request_terminate_proc = target_proc.clone();
+            request_signal_proc.take()
+        }) else {
+            error!("No process was requested to be signalled");
+            return;
+        };
+
+        let signal_name = signal_name(signal);
+        app_state.set_admin_retry_action(SharedString::from(""));
+        if let Err(e) = handle.send_signal(signal) {
+            error!("Failed to send {signal_name} to process: {e}");
+            app_state.set_status_message(SharedString::from(format!(
+                "Failed to send {signal_name} to {name} ({}): {e}",
+                handle.identity()
+            )));
+            if e.is_permission_denied() {
+                offer_admin_retry(
+                    &backend_state_clone,
+                    &app_state,
+                    format!("send {signal_name} to {name} ({})", handle.identity()),
+                    HelperRequest {
+                        identity: handle.identity(),
+                        action: HelperAction::Signal(signal),
+                    },
+                );
+            }
+            return;
         }
 
-        AppWindowState::get(&app_window).set_to_be_terminated_process(SharedString::from(format!(
-            "{} ({})",
-            target_proc.as_ref().map_or("Unknown", |(name, _)| name),
-            target_proc.as_ref().map_or(-1, |(_, id)| *id)
+        app_state.set_status_message(SharedString::from(format!(
+            "Sent {signal_name} to {name} ({})",
+            handle.identity()
         )));
+        app_state.set_to_be_signalled_process(SharedString::from(""));
+
+        if signal == libc::SIGTERM || signal == libc::SIGKILL {
+            app_state.set_force_kill_candidate(SharedString::from(""));
+            let grace_period = Duration::from_secs(
+                u64::try_from(app_state.get_grace_period_seconds()).unwrap_or_default(),
+            );
+            tokio::spawn(track_termination(
+                ui_handle.clone(),
+                backend_state_clone.clone(),
+                (name, handle),
+                signal,
+                grace_period,
+                app_state.get_force_kill_after_grace(),
+            ));
+        }
     });
 
-    let backend_state_clone = backend_state.clone();
     let ui_handle = ui.as_weak();
-    ui.on_confirm_terminate_process(move || {
+    let backend_state_clone = backend_state.clone();
+    ui.on_force_kill_process(move || {
         let Some(app_window) = ui_handle.upgrade() else {
             error!("Failed to upgrade UI handle");
             return;
         };
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_force_kill_candidate(SharedString::from(""));
 
-        if let Err(e) = signal::kill(
-            Pid::from_raw(
-                backend_state_clone
-                    .request_terminate_proc
-                    .read()
-                    .expect("Failed to get read lock on request terminate process")
-                    .as_ref()
-                    .map_or(-1, |(_, id)| *id),
-            ),
-            signal::Signal::SIGTERM,
-        ) {
-            error!("Failed to terminate process: {e}");
-        } else {
-            AppWindowState::get(&app_window).set_to_be_terminated_process(SharedString::from(""));
+        let Some((name, handle)) = ({
+            let Ok(mut force_kill_proc) = backend_state_clone.force_kill_proc.write() else {
+                error!("Failed to get write lock on force kill process");
+                return;
+            };
+            force_kill_proc.take()
+        }) else {
+            return;
+        };
+
+        if let Err(e) = handle.send_signal(libc::SIGKILL) {
+            error!("Failed to send SIGKILL to process: {e}");
+            app_state.set_status_message(SharedString::from(format!(
+                "Failed to send SIGKILL to {name} ({}): {e}",
+                handle.identity()
+            )));
+            if e.is_permission_denied() {
+                offer_admin_retry(
+                    &backend_state_clone,
+                    &app_state,
+                    format!("send SIGKILL to {name} ({})", handle.identity()),
+                    HelperRequest {
+                        identity: handle.identity(),
+                        action: HelperAction::Signal(libc::SIGKILL),
+                    },
+                );
+            }
+            return;
         }
+
+        tokio::spawn(track_termination(
+            ui_handle.clone(),
+            backend_state_clone.clone(),
+            (name, handle),
+            libc::SIGKILL,
+            KILL_GRACE_PERIOD,
+            false,
+        ));
     });
 
     let ui_handle = ui.as_weak();
     let backend_state_clone = backend_state.clone();
-    let refresh_thread = tokio::spawn(async move {
-        loop {
-            let backend_state_clone = backend_state_clone.clone();
-            let ui_handle = ui_handle.clone();
+    let f5_req_send_clone = f5_req_send.clone();
+    ui.on_renice_process(move |nice| {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_admin_retry_action(SharedString::from(""));
 
-            let _ = slint::invoke_from_event_loop(move || {
-                let Ok(sort_order) = backend_state_clone.sort_order.read() else {
-                    error!("Failed to get read lock on sort order");
-                    return;
-                };
-                let Ok(sort_by) = backend_state_clone.sort_by.read() else {
-                    error!("Failed to get read lock on sort by");
-                    return;
-                };
-                let Ok(search_term) = backend_state_clone.search_term.read() else {
-                    error!("Failed to get read lock on search term");
-                    return;
-                };
-                let Some(app_window) = ui_handle.upgrade() else {
-                    error!("Failed to upgrade UI handle");
-                    return;
-                };
+        let Some((name, row)) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return;
+            };
+            selected_proc.clone()
+        }) else {
+            return;
+        };
 
-                let Ok(processes) = get_sorted_process_list(&sort_by, &sort_order, &search_term)
-                else {
-                    error!("Failed to get sorted process list");
-                    return;
-                };
-                let Ok(mut curr_proc_list) = backend_state_clone.curr_proc_list.write() else {
-                    error!("Failed to get write lock on current process list");
-                    return;
-                };
-                *curr_proc_list = processes;
+        // on a thread row, only that thread is reniced
+        let (description, handle) = open_row(&backend_state_clone, &name, row);
+        let result = handle.and_then(|handle| match row.thread_of {
+            Some(_) => handle.set_thread_nice(row.identity, nice),
+            None => handle.set_nice(nice),
+        });
 
-                AppWindowState::get(&app_window)
-                    .set_procs(curr_proc_list.to_standard_list_view_items());
-            });
+        let Err(e) = result else {
+            app_state.set_status_message(SharedString::from(format!(
+                "Set the nice value of {description} to {nice}"
+            )));
+            if let Err(e) = f5_req_send_clone.try_send(()) {
+                error!("Failed to send F5 request: {e}");
+            }
+            return;
+        };
 
-            select! {
-                _ = tokio::time::sleep(Duration::from_secs(3)) => (),
-                _ = f5_req_recv.recv() => (),
+        error!("Failed to renice process: {e}");
+        // setpriority fails with EACCES when lowering the nice value, and with EPERM for
+        // the processes of other users
+        let reason = match e.errno() {
+            Some(Errno::EACCES) => {
+                format!("{e}, only administrators can lower the nice value")
+            }
+            Some(Errno::EPERM) => {
+                format!("{e}, the process belongs to another user")
             }
+            _ => e.to_string(),
+        };
+        app_state.set_status_message(SharedString::from(format!(
+            "Failed to set the nice value of {description} to {nice}: {reason}"
+        )));
+        if e.is_permission_denied() && row.thread_of.is_none() {
+            offer_admin_retry(
+                &backend_state_clone,
+                &app_state,
+                format!("set the nice value of {description} to {nice}"),
+                HelperRequest {
+                    identity: row.identity,
+                    action: HelperAction::Renice(nice),
+                },
+            );
         }
     });
 
-    ui.run().expect("There was an error running the UI");
-    refresh_thread.abort();
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_set_oom_score_adj(move |adj| {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_admin_retry_action(SharedString::from(""));
 
-    Ok(())
+        let Some((name, row)) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return;
+            };
+            selected_proc.clone()
+        }) else {
+            return;
+        };
+        let Ok(adj) = i16::try_from(adj) else {
+            error!("Invalid OOM score adjustment: {adj}");
+            return;
+        };
+
+        // on a thread row, the process the thread belongs to is adjusted
+        let (description, handle) = open_row(&backend_state_clone, &name, row);
+        let Err(e) = handle.and_then(|handle| handle.set_oom_score_adj(adj)) else {
+            app_state.set_status_message(SharedString::from(format!(
+                "Set the OOM score adjustment of {description} to {adj}"
+            )));
+            return;
+        };
+
+        error!("Failed to set the OOM score adjustment: {e}");
+        // lowering the adjustment below its minimum needs CAP_SYS_RESOURCE
+        let reason = match e {
+            ActionError::Os(Errno::EACCES) => {
+                format!("{e}, only administrators can lower the OOM score adjustment")
+            }
+            e => e.to_string(),
+        };
+        app_state.set_status_message(SharedString::from(format!(
+            "Failed to set the OOM score adjustment of {description} to {adj}: {reason}"
+        )));
+        if e.is_permission_denied() {
+            offer_admin_retry(
+                &backend_state_clone,
+                &app_state,
+                format!("set the OOM score adjustment of {description} to {adj}"),
+                HelperRequest {
+                    identity: row
+                        .thread_of
+                        .and_then(|process_id| {
+                            process_identity_of(&backend_state_clone, process_id)
+                        })
+                        .unwrap_or(row.identity),
+                    action: HelperAction::OomScoreAdj(adj),
+                },
+            );
+        }
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_request_affinity(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return false;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some((name, row)) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return false;
+            };
+            selected_proc.clone()
+        }) else {
+            return false;
+        };
+        let allowed_cpus = listed_row(&backend_state_clone, row).and_then(|proc| proc.allowed_cpus);
+
+        let (description, handle) = open_row(&backend_state_clone, &name, row);
+        let handle = match handle {
+            Ok(handle) => handle,
+            Err(e) => {
+                app_state.set_status_message(SharedString::from(format!(
+                    "Cannot change the CPU affinity of {description}: {e}"
+                )));
+                return false;
+            }
+        };
+
+        // everything is allowed when the affinity could not be read
+        let cpus = online_cpus()
+            .into_iter()
+            .map(|cpu| AffinityCpu {
+                cpu: cpu as i32,
+                allowed: allowed_cpus.as_deref().is_none_or(|ranges| {
+                    ranges
+                        .iter()
+                        .any(|&(first, last)| (first..=last).contains(&cpu))
+                }),
+            })
+            .collect::<Vec<_>>();
+        app_state.set_affinity_cpus(ModelRc::new(VecModel::from(cpus)));
+        app_state.set_to_be_pinned_process(SharedString::from(description));
+        app_state.set_to_be_pinned_is_thread(row.thread_of.is_some());
+
+        let Ok(mut request_affinity) = backend_state_clone.request_affinity.write() else {
+            error!("Failed to get write lock on request affinity");
+            return false;
+        };
+        *request_affinity = Some((name, handle, row.thread_of.map(|_| row.identity)));
+        true
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    let f5_req_send_clone = f5_req_send.clone();
+    ui.on_confirm_affinity(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some((name, handle, thread)) = ({
+            let Ok(mut request_affinity) = backend_state_clone.request_affinity.write() else {
+                error!("Failed to get write lock on request affinity");
+                return;
+            };
+            request_affinity.take()
+        }) else {
+            error!("No process was requested to be pinned");
+            return;
+        };
+
+        let cpus = app_state
+            .get_affinity_cpus()
+            .iter()
+            .filter(|cpu| cpu.allowed)
+            .filter_map(|cpu| u32::try_from(cpu.cpu).ok())
+            .collect::<Vec<_>>();
+        let cpu_list = format_cpu_list(&cpu_ranges(cpus.iter().copied()));
+        let (description, result) = match thread {
+            Some(thread) => (
+                format!("{name} (thread {thread} of {})", handle.identity()),
+                handle.set_thread_affinity(thread, &cpus),
+            ),
+            None => (
+                format!("{name} ({})", handle.identity()),
+                handle.set_affinity(&cpus, app_state.get_affinity_all_threads()),
+            ),
+        };
+
+        let Err(e) = result else {
+            app_state.set_status_message(SharedString::from(format!(
+                "Restricted {description} to CPUs {cpu_list}"
+            )));
+            if let Err(e) = f5_req_send_clone.try_send(()) {
+                error!("Failed to send F5 request: {e}");
+            }
+            return;
+        };
+
+        error!("Failed to set CPU affinity: {e}");
+        let reason = match e.errno() {
+            Some(Errno::EINVAL) if cpus.is_empty() => "no CPU is selected".to_string(),
+            // the cpuset of the process only lets it use some of the online CPUs
+            Some(Errno::EINVAL) => {
+                format!("{e}, none of the CPUs is in the cpuset of the process")
+            }
+            Some(Errno::EPERM) => {
+                format!("{e}, the process belongs to another user")
+            }
+            _ => e.to_string(),
+        };
+        app_state.set_status_message(SharedString::from(format!(
+            "Failed to restrict {description} to CPUs {cpu_list}: {reason}"
+        )));
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_request_scheduling(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return false;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some((name, row)) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return false;
+            };
+            selected_proc.clone()
+        }) else {
+            return false;
+        };
+        let listed = listed_row(&backend_state_clone, row);
+        let sched_policy = listed.as_ref().and_then(|proc| proc.sched_policy);
+        let io_priority = listed.as_ref().and_then(|proc| proc.io_priority);
+
+        let (description, handle) = open_row(&backend_state_clone, &name, row);
+        let handle = match handle {
+            Ok(handle) => handle,
+            Err(e) => {
+                app_state.set_status_message(SharedString::from(format!(
+                    "Cannot change the scheduling of {description}: {e}"
+                )));
+                return false;
+            }
+        };
+
+        let sched_policy_or_default = sched_policy.unwrap_or(SchedPolicy::Other);
+        app_state.set_sched_policy(SharedString::from(sched_policy_or_default.name()));
+        app_state.set_rt_priority(sched_policy_or_default.rt_priority().unwrap_or(1) as i32);
+        let io_priority_or_default = io_priority.unwrap_or(IoPriority::None);
+        app_state.set_io_class(SharedString::from(io_priority_or_default.class_name()));
+        app_state.set_io_level(i32::from(io_priority_or_default.level().unwrap_or(4)));
+        app_state.set_to_be_scheduled_process(SharedString::from(description.clone()));
+
+        let Ok(mut request_scheduling) = backend_state_clone.request_scheduling.write() else {
+            error!("Failed to get write lock on request scheduling");
+            return false;
+        };
+        *request_scheduling = Some(SchedulingRequest {
+            description,
+            handle,
+            thread: row.thread_of.map(|_| row.identity),
+            sched_policy: sched_policy_or_default,
+            io_priority: io_priority_or_default,
+        });
+        true
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    let f5_req_send_clone = f5_req_send.clone();
+    ui.on_confirm_scheduling(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some(request) = ({
+            let Ok(mut request_scheduling) = backend_state_clone.request_scheduling.write()
+            else {
+                error!("Failed to get write lock on request scheduling");
+                return;
+            };
+            request_scheduling.take()
+        }) else {
+            error!("No process was requested to be rescheduled");
+            return;
+        };
+        let description = &request.description;
+
+        // a SCHED_DEADLINE process keeps its policy unless another one is picked, and a
+        // policy that could not be read is not replaced by the default the dialog showed
+        let sched_policy = SchedPolicy::parse(
+            &app_state.get_sched_policy(),
+            u32::try_from(app_state.get_rt_priority()).unwrap_or(1),
+        )
+        .filter(|policy| *policy != request.sched_policy);
+        let io_priority = IoPriority::parse(
+            &app_state.get_io_class(),
+            u8::try_from(app_state.get_io_level()).unwrap_or(4),
+        )
+        .filter(|priority| *priority != request.io_priority);
+
+        let mut changes = vec![];
+        if let Some(policy) = sched_policy {
+            let result = match request.thread {
+                Some(thread) => request.handle.set_thread_sched_policy(thread, policy),
+                None => request.handle.set_sched_policy(policy),
+            };
+            if let Err(e) = result {
+                error!("Failed to set scheduling policy: {e}");
+                // sched_setscheduler fails with EPERM both for the processes of other
+                // users and for real-time policies without CAP_SYS_NICE or RLIMIT_RTPRIO
+                let reason = match e.errno() {
+                    Some(Errno::EPERM) => format!(
+                        "{e}, real-time policies and the processes of other users need administrator rights"
+                    ),
+                    _ => e.to_string(),
+                };
+                app_state.set_status_message(SharedString::from(format!(
+                    "Failed to set the policy of {description} to {policy}: {reason}"
+                )));
+                return;
+            }
+            changes.push(format!("the policy to {policy}"));
+        }
+        if let Some(priority) = io_priority {
+            let result = match request.thread {
+                Some(thread) => request.handle.set_thread_io_priority(thread, priority),
+                None => request.handle.set_io_priority(priority),
+            };
+            if let Err(e) = result {
+                error!("Failed to set I/O priority: {e}");
+                // ioprio_set fails with EPERM both for the processes of other users and
+                // for the realtime class without CAP_SYS_ADMIN
+                let reason = match e.errno() {
+                    Some(Errno::EPERM) => format!(
+                        "{e}, the realtime class and the processes of other users need administrator rights"
+                    ),
+                    _ => e.to_string(),
+                };
+                // the policy may have been changed already
+                let done = changes
+                    .iter()
+                    .map(|change| format!("Set {change} of {description}. "))
+                    .collect::<String>();
+                app_state.set_status_message(SharedString::from(format!(
+                    "{done}Failed to set the I/O priority of {description} to {priority}: {reason}"
+                )));
+                return;
+            }
+            changes.push(format!("the I/O priority to {priority}"));
+        }
+
+        app_state.set_status_message(SharedString::from(if changes.is_empty() {
+            format!("The scheduling of {description} is unchanged")
+        } else {
+            format!("Set {} of {description}", changes.join(" and "))
+        }));
+        if let Err(e) = f5_req_send_clone.try_send(()) {
+            error!("Failed to send F5 request: {e}");
+        }
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_retry_as_administrator(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_admin_retry_action(SharedString::from(""));
+
+        let Some((description, request)) = ({
+            let Ok(mut admin_retry) = backend_state_clone.admin_retry.write() else {
+                error!("Failed to get write lock on administrator retry");
+                return;
+            };
+            admin_retry.take()
+        }) else {
+            return;
+        };
+
+        app_state.set_status_message(SharedString::from(format!(
+            "Waiting for authentication to {description}"
+        )));
+
+        // pkexec blocks until the user is done with the authentication dialog
+        let ui_handle = ui_handle.clone();
+        tokio::task::spawn_blocking(move || {
+            let message = match request.execute_as_administrator() {
+                Ok(()) => format!("Managed to {description} as administrator"),
+                Err(e) => {
+                    error!("Failed to {description} as administrator: {e}");
+                    format!("Failed to {description} as administrator: {e}")
+                }
+            };
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(app_window) = ui_handle.upgrade() else {
+                    error!("Failed to upgrade UI handle");
+                    return;
+                };
+                AppWindowState::get(&app_window).set_status_message(SharedString::from(message));
+            });
+        });
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_request_signal_tree(move |signal| {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return false;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some(signal) = parse_signal(&signal) else {
+            app_state.set_status_message(SharedString::from(format!(
+                "Unknown signal: \"{}\"",
+                signal.trim()
+            )));
+            return false;
+        };
+
+        let Some((name, row)) = ({
+            let Ok(selected_proc) = backend_state_clone.selected_proc.read() else {
+                error!("Failed to get read lock on selected process");
+                return false;
+            };
+            selected_proc.clone()
+        }) else {
+            return false;
+        };
+        if let Some(process_id) = row.thread_of {
+            app_state.set_status_message(SharedString::from(format!(
+                "{name} is a thread, select its process ({process_id}) to signal the tree"
+            )));
+            return false;
+        }
+        let identity = row.identity;
+        if contains_task_manager(identity) {
+            app_state.set_status_message(SharedString::from(format!(
+                "Cannot send {} to the tree of {name} ({identity}): it contains the task manager",
+                signal_name(signal)
+            )));
+            return false;
+        }
+
+        let tree = match ProcessTree::open(identity) {
+            Ok(tree) if !tree.members().is_empty() => tree,
+            Ok(_) => {
+                app_state.set_status_message(SharedString::from(format!(
+                    "Cannot send {} to {name} ({identity}): the process has exited",
+                    signal_name(signal)
+                )));
+                return false;
+            }
+            Err(e) => {
+                error!("Failed to collect the process tree: {e}");
+                app_state.set_status_message(SharedString::from(format!(
+                    "Cannot send {} to {name} ({identity}): {e}",
+                    signal_name(signal)
+                )));
+                return false;
+            }
+        };
+
+        app_state.set_to_be_signalled_process(SharedString::from(format!(
+            "{name} ({identity}), {} processes in total",
+            tree.members().len()
+        )));
+        app_state.set_to_be_sent_signal(SharedString::from(signal_name(signal)));
+        app_state.set_to_be_signalled_tree(ModelRc::new(VecModel::from(
+            tree.members()
+                .iter()
+                .map(|member| {
+                    StandardListViewItem::from(SharedString::from(format!(
+                        "{}{} ({})",
+                        "  ".repeat(member.depth * 2),
+                        member.name,
+                        member.handle.identity()
+                    )))
+                })
+                .collect::<Vec<_>>(),
+        )));
+
+        let Ok(mut request_signal_tree) = backend_state_clone.request_signal_tree.write() else {
+            error!("Failed to get write lock on request signal tree");
+            return false;
+        };
+        *request_signal_tree = Some((name, identity, tree, signal));
+        true
+    });
+
+    let backend_state_clone = backend_state.clone();
+    let ui_handle = ui.as_weak();
+    ui.on_confirm_signal_tree(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_to_be_signalled_tree(ModelRc::default());
+
+        app_state.set_admin_retry_action(SharedString::from(""));
+
+        let Some((name, identity, tree, signal)) = ({
+            let Ok(mut request_signal_tree) = backend_state_clone.request_signal_tree.write()
+            else {
+                error!("Failed to get write lock on request signal tree");
+                return;
+            };
+            request_signal_tree.take()
+        }) else {
+            error!("No process tree was requested to be signalled");
+            return;
+        };
+
+        let signal_name = signal_name(signal);
+        let failures = tree.send_signal(signal);
+        let denied = failures.iter().any(|(_, e)| e.is_permission_denied());
+        let failures = failures
+            .into_iter()
+            .map(|(member, e)| format!("{} ({}): {e}", member.name, member.handle.identity()))
+            .collect::<Vec<_>>();
+        if denied {
+            offer_admin_retry(
+                &backend_state_clone,
+                &app_state,
+                format!("send {signal_name} to the tree of {name} ({identity})"),
+                HelperRequest {
+                    identity,
+                    action: HelperAction::SignalTree(signal),
+                },
+            );
+        }
+        if failures.is_empty() {
+            app_state.set_status_message(SharedString::from(format!(
+                "Sent {signal_name} to {} processes",
+                tree.members().len()
+            )));
+        } else {
+            error!("Failed to send {signal_name} to part of the process tree: {failures:?}");
+            app_state.set_status_message(SharedString::from(format!(
+                "Failed to send {signal_name} to {} of {} processes: {}",
+                failures.len(),
+                tree.members().len(),
+                failures.join(", ")
+            )));
+        }
+
+        if signal == libc::SIGSTOP || signal == libc::SIGCONT {
+            return;
+        }
+
+        // give the tree a moment to handle the signal before reporting the survivors
+        let ui_handle = ui_handle.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let survivors = tree
+                .survivors()
+                .iter()
+                .map(|member| format!("{} ({})", member.name, member.handle.identity()))
+                .collect::<Vec<_>>();
+            let message = if survivors.is_empty() {
+                format!(
+                    "All {} processes exited after {signal_name}",
+                    tree.members().len()
+                )
+            } else {
+                format!(
+                    "{} of {} processes survived {signal_name}: {}",
+                    survivors.len(),
+                    tree.members().len(),
+                    survivors.join(", ")
+                )
+            };
+
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(app_window) = ui_handle.upgrade() else {
+                    error!("Failed to upgrade UI handle");
+                    return;
+                };
+                AppWindowState::get(&app_window).set_status_message(SharedString::from(message));
+            });
+        });
+    });
+
+    let backend_state_clone = backend_state.clone();
+    ui.on_select_deleted_file(move |row| {
+        let Ok(deleted_files) = backend_state_clone.deleted_files.read() else {
+            error!("Failed to get read lock on deleted files");
+            return;
+        };
+        let Ok(mut selected) = backend_state_clone.selected_deleted_file.write() else {
+            error!("Failed to get write lock on selected deleted file");
+            return;
+        };
+        *selected = usize::try_from(row)
+            .ok()
+            .and_then(|row| deleted_files.get(row))
+            .map(DeletedFile::key);
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_request_signal_deleted_file_owner(move |signal| {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return false;
+        };
+        let app_state = AppWindowState::get(&app_window);
+
+        let Some(signal) = parse_signal(&signal) else {
+            app_state.set_status_message(SharedString::from(format!(
+                "Unknown signal: \"{}\"",
+                signal.trim()
+            )));
+            return false;
+        };
+        let Some(deleted_file) = selected_deleted_file(&backend_state_clone) else {
+            return false;
+        };
+
+        request_signal_process(
+            &backend_state_clone,
+            &app_state,
+            (deleted_file.owner_name, deleted_file.owner),
+            signal,
+        )
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_request_truncate_deleted_file(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return false;
+        };
+        let Some(deleted_file) = selected_deleted_file(&backend_state_clone) else {
+            return false;
+        };
+
+        AppWindowState::get(&app_window).set_to_be_truncated_file(SharedString::from(format!(
+            "{} ({}) held open by {} ({}) on fd {}",
+            deleted_file.path.display(),
+            human_readable_byte(deleted_file.size),
+            deleted_file.owner_name,
+            deleted_file.owner,
+            deleted_file.fd
+        )));
+        let Ok(mut request_truncate) = backend_state_clone.request_truncate.write() else {
+            error!("Failed to get write lock on request truncate");
+            return false;
+        };
+        *request_truncate = Some(deleted_file);
+        true
+    });
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    ui.on_confirm_truncate_deleted_file(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let Some(deleted_file) = ({
+            let Ok(mut request_truncate) = backend_state_clone.request_truncate.write() else {
+                error!("Failed to get write lock on request truncate");
+                return;
+            };
+            request_truncate.take()
+        }) else {
+            return;
+        };
+
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_admin_retry_action(SharedString::from(""));
+
+        let description = format!(
+            "{} held by {} ({})",
+            deleted_file.path.display(),
+            deleted_file.owner_name,
+            deleted_file.owner
+        );
+        match deleted_file.truncate() {
+            Ok(()) => app_state.set_status_message(SharedString::from(format!(
+                "Truncated {description}, freeing {}",
+                human_readable_byte(deleted_file.size)
+            ))),
+            Err(e) => {
+                error!("Failed to truncate deleted file: {e}");
+                // EPERM is the refusal to empty a file that was linked again
+                let reason = match e.errno() {
+                    Some(Errno::EPERM) => format!("{e}, the file is no longer deleted"),
+                    _ => e.to_string(),
+                };
+                app_state.set_status_message(SharedString::from(format!(
+                    "Cannot truncate {description}: {reason}"
+                )));
+                // the fds of another user's process can only be reached by root
+                if e.errno() == Some(Errno::EACCES) {
+                    offer_admin_retry(
+                        &backend_state_clone,
+                        &app_state,
+                        format!("truncate {description}"),
+                        HelperRequest {
+                            identity: deleted_file.owner,
+                            action: deleted_file.truncate_action(),
+                        },
+                    );
+                }
+            }
+        }
+        refresh_details(ui_handle.clone(), backend_state_clone.clone());
+    });
+
+    let ui_handle = ui.as_weak();
+    let f5_req_send_clone = f5_req_send.clone();
+    let backend_state_clone = backend_state.clone();
+    ui.on_toggle_column(move |index, visible| {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+        let Ok(index) = usize::try_from(index) else {
+            error!("Invalid column index: {index}");
+            return;
+        };
+        let Ok(mut visible_columns) = backend_state_clone.visible_columns.write() else {
+            error!("Failed to get write lock on visible columns");
+            return;
+        };
+        visible_columns.retain(|&column| column != index);
+        if visible {
+            visible_columns.push(index);
+            visible_columns.sort_unstable();
+        }
+        // an empty table could not be sorted or selected anymore
+        if visible_columns.is_empty() {
+            visible_columns.push(index);
+        }
+        show_columns(&app_state, &visible_columns);
+        // the rows are filled again with the cells of the shown columns
+        app_state.set_procs(ModelRc::new(ProcessTableModel::default()));
+        let _ = f5_req_send_clone.try_send(());
+    });
+
+    {
+        let app_state = AppWindowState::get(&ui);
+        app_state.set_procs(ModelRc::new(ProcessTableModel::default()));
+        match backend_state.visible_columns.read() {
+            Ok(visible_columns) => show_columns(&app_state, &visible_columns),
+            Err(_) => error!("Failed to get read lock on visible columns"),
+        }
+    }
+
+    let ui_handle = ui.as_weak();
+    let backend_state_clone = backend_state.clone();
+    let refresh_thread = tokio::spawn(async move {
+        loop {
+            let cancelled = Arc::new(AtomicBool::new(false));
+            let mut scan = tokio::task::spawn_blocking({
+                let backend_state = backend_state_clone.clone();
+                let cancelled = cancelled.clone();
+                move || scan_processes(&backend_state, &cancelled)
+            });
+
+            // a newer search or sort request makes the running scan pointless
+            let scan_result = select! {
+                scan_result = &mut scan => scan_result,
+                _ = f5_req_recv.recv() => {
+                    cancelled.store(true, Ordering::Relaxed);
+                    let _ = scan.await;
+                    continue;
+                }
+            };
+            let Ok(Some((processes, snapshot, notice))) = scan_result else {
+                error!("Failed to scan processes: {scan_result:?}");
+                select! {
+                    _ = tokio::time::sleep(Duration::from_secs(3)) => (),
+                    _ = f5_req_recv.recv() => (),
+                }
+                continue;
+            };
+
+            let _ = slint::invoke_from_event_loop({
+                let backend_state_clone = backend_state_clone.clone();
+                let ui_handle = ui_handle.clone();
+                move || {
+                    let Some(app_window) = ui_handle.upgrade() else {
+                        error!("Failed to upgrade UI handle");
+                        return;
+                    };
+                    let Ok(mut curr_proc_list) = backend_state_clone.curr_proc_list.write() else {
+                        error!("Failed to get write lock on current process list");
+                        return;
+                    };
+                    *curr_proc_list = processes;
+
+                    let app_state = AppWindowState::get(&app_window);
+                    let procs = app_state.get_procs();
+                    let Some(table) = procs.as_any().downcast_ref::<ProcessTableModel>() else {
+                        error!("The process table model was replaced");
+                        return;
+                    };
+                    // the cells of the hidden columns are left out
+                    let snapshot = {
+                        let Ok(visible_columns) = backend_state_clone.visible_columns.read() else {
+                            error!("Failed to get read lock on visible columns");
+                            return;
+                        };
+                        snapshot
+                            .into_iter()
+                            .map(|(row, cells)| {
+                                let cells = visible_columns
+                                    .iter()
+                                    .filter_map(|&column| cells.get(column).cloned())
+                                    .collect();
+                                (row, cells)
+                            })
+                            .collect()
+                    };
+                    table.update(snapshot);
+                    app_state.set_search_notice(SharedString::from(notice.unwrap_or_default()));
+
+                    // keep the selection on the same process wherever it moved to
+                    let Ok(mut selected_proc) = backend_state_clone.selected_proc.write() else {
+                        error!("Failed to get write lock on selected process");
+                        return;
+                    };
+                    let Some((name, selected_row)) = selected_proc.clone() else {
+                        return;
+                    };
+                    if let Some(row) = curr_proc_list
+                        .iter()
+                        .position(|(proc, _)| proc.row_identity() == selected_row)
+                    {
+                        app_state.set_selected_row(row as i32);
+                        return;
+                    }
+
+                    if let Err(e) = selected_row.identity.verify() {
+                        app_state.set_status_message(SharedString::from(format!(
+                            "{name} ({}) is no longer selected: {e}",
+                            selected_row.identity
+                        )));
+                    }
+                    *selected_proc = None;
+                    app_state.set_selected_row(-1);
+                    app_state.set_has_selected_process(false);
+                }
+            });
+            // the open handles change as often as the process list
+            refresh_details(ui_handle.clone(), backend_state_clone.clone());
+
+            select! {
+                _ = tokio::time::sleep(Duration::from_secs(3)) => (),
+                _ = f5_req_recv.recv() => (),
+            }
+        }
+    });
+
+    ui.run().expect("There was an error running the UI");
+    refresh_thread.abort();
+
+    Ok(())
+}
+
+/// The identity of a process currently listed, looked up by its PID.
+fn process_identity_of(
+    backend_state: &BackendAppState,
+    process_id: MyProcessID,
+) -> Option<ProcessIdentity> {
+    let Ok(proc_list) = backend_state.curr_proc_list.read() else {
+        error!("Failed to get read lock on current process list");
+        return None;
+    };
+    proc_list
+        .iter()
+        .find(|(proc, _)| proc.id == process_id && proc.thread_of.is_none())
+        .map(|(proc, _)| proc.identity())
+}
+
+/// Prepares the confirmation of a signal for a single thread, only the signals that can
+/// be meant for one thread are accepted.
+fn request_signal_thread(
+    backend_state: &BackendAppState,
+    app_state: &AppWindowState,
+    (name, thread, process_id): (String, ProcessIdentity, MyProcessID),
+    signal: i32,
+) -> bool {
+    if !is_thread_signal(signal) {
+        app_state.set_status_message(SharedString::from(format!(
+            "{} acts on the whole process, select the process ({process_id}) to send it",
+            signal_name(signal)
+        )));
+        return false;
+    }
+
+    let Some(process) = process_identity_of(backend_state, process_id) else {
+        app_state.set_status_message(SharedString::from(format!(
+            "Cannot send {} to {name} (thread {thread}): process {process_id} is gone",
+            signal_name(signal)
+        )));
+        return false;
+    };
+    let handle = match ProcessHandle::open(process) {
+        Ok(handle) => handle,
+        Err(e) => {
+            app_state.set_status_message(SharedString::from(format!(
+                "Cannot send {} to {name} (thread {thread} of {process}): {e}",
+                signal_name(signal)
+            )));
+            return false;
+        }
+    };
+
+    app_state.set_to_be_signalled_process(SharedString::from(format!(
+        "{name} (thread {thread} of {process})"
+    )));
+    app_state.set_to_be_sent_signal(SharedString::from(signal_name(signal)));
+    app_state.set_to_be_signalled_tree(ModelRc::default());
+
+    let Ok(mut request_signal_thread) = backend_state.request_signal_thread.write() else {
+        error!("Failed to get write lock on request signal thread");
+        return false;
+    };
+    *request_signal_thread = Some((name, handle, thread, signal));
+    if let Ok(mut request_signal_proc) = backend_state.request_signal_proc.write() {
+        *request_signal_proc = None;
+    }
+    true
+}
+
+/// Collects, filters and sorts the processes with the current search and sort settings,
+/// along with the rows to display for each of them and a notice about what the search had
+/// to skip. Returns `None` when the scan was cancelled or failed.
+#[allow(clippy::type_complexity)]
+fn scan_processes(
+    backend_state: &BackendAppState,
+    cancelled: &AtomicBool,
+) -> Option<(
+    Vec<(MyProcess, usize)>,
+    Vec<(RowIdentity, Vec<StandardListViewItem>)>,
+    Option<String>,
+)> {
+    // settings changed during the scan are picked up by the scan they trigger
+    let (sort_by, sort_order, search_term, memory_metric, expanded_procs) = {
+        let Ok(sort_order) = backend_state.sort_order.read() else {
+            error!("Failed to get read lock on sort order");
+            return None;
+        };
+        let Ok(sort_by) = backend_state.sort_by.read() else {
+            error!("Failed to get read lock on sort by");
+            return None;
+        };
+        let Ok(search_term) = backend_state.search_term.read() else {
+            error!("Failed to get read lock on search term");
+            return None;
+        };
+        let Ok(memory_metric) = backend_state.memory_metric.read() else {
+            error!("Failed to get read lock on memory metric");
+            return None;
+        };
+        let Ok(expanded_procs) = backend_state.expanded_procs.read() else {
+            error!("Failed to get read lock on expanded processes");
+            return None;
+        };
+        (
+            sort_by.clone(),
+            *sort_order,
+            search_term.clone(),
+            *memory_metric,
+            expanded_procs.clone(),
+        )
+    };
+    let Ok(mut scan_state) = backend_state.scan_state.write() else {
+        error!("Failed to get write lock on scan state");
+        return None;
+    };
+    match backend_state.recheck_outdated_files.write() {
+        Ok(mut recheck) if *recheck => {
+            scan_state.outdated_files.invalidate();
+            *recheck = false;
+        }
+        Ok(_) => (),
+        Err(_) => error!("Failed to get write lock on recheck outdated files"),
+    }
+
+    let processes = match get_sorted_process_list(
+        &sort_by,
+        &sort_order,
+        &search_term,
+        memory_metric,
+        &expanded_procs,
+        &mut scan_state,
+        cancelled,
+    ) {
+        Ok(processes) => processes?,
+        Err(e) => {
+            error!("Failed to get sorted process list: {e}");
+            return None;
+        }
+    };
+    let snapshot = processes
+        .iter()
+        .map(|(process, _)| process.row_identity())
+        .zip(processes.to_standard_list_view_items(memory_metric))
+        .collect();
+    // the search cannot tell these processes apart from those that do not match
+    let notice = (scan_state.unreadable_environments > 0).then(|| {
+        format!(
+            "@env skipped {} processes whose environment cannot be read: permission denied",
+            scan_state.unreadable_environments
+        )
+    });
+    Some((processes, snapshot, notice))
+}
+
+/// Watches a process that was sent SIGTERM or SIGKILL and reports how it ended. A process
+/// still running after the grace period of a SIGTERM is either sent SIGKILL right away
+/// or offered to the user for it.
+async fn track_termination(
+    ui_handle: slint::Weak<AppWindow>,
+    backend_state: Arc<BackendAppState>,
+    (name, handle): (String, ProcessHandle),
+    signal: i32,
+    grace_period: Duration,
+    force_kill: bool,
+) {
+    let identity = handle.identity();
+    let mut signal = signal;
+    let mut fate = ProcessFate::wait_for_exit(identity, grace_period).await;
+
+    if signal == libc::SIGTERM && !fate.is_terminated() && force_kill {
+        match handle.send_signal(libc::SIGKILL) {
+            Ok(()) => {
+                signal = libc::SIGKILL;
+                fate = ProcessFate::wait_for_exit(identity, KILL_GRACE_PERIOD).await;
+            }
+            Err(e) => error!("Failed to send SIGKILL to process: {e}"),
+        }
+    }
+
+    let message = format!("After {}, {name} ({identity}) {fate}", signal_name(signal));
+    let offer_force_kill = signal == libc::SIGTERM && !fate.is_terminated();
+
+    let _ = slint::invoke_from_event_loop(move || {
+        let Some(app_window) = ui_handle.upgrade() else {
+            error!("Failed to upgrade UI handle");
+            return;
+        };
+        let app_state = AppWindowState::get(&app_window);
+        app_state.set_status_message(SharedString::from(message));
+
+        if !offer_force_kill {
+            return;
+        }
+        let Ok(mut force_kill_proc) = backend_state.force_kill_proc.write() else {
+            error!("Failed to get write lock on force kill process");
+            return;
+        };
+        app_state.set_force_kill_candidate(SharedString::from(format!("{name} ({identity})")));
+        *force_kill_proc = Some((name, handle));
+    });
+}
+
+/// Remembers the process to send the signal to once the user confirms it.
+fn request_signal_process(
+    backend_state: &BackendAppState,
+    app_state: &AppWindowState,
+    (name, identity): (String, ProcessIdentity),
+    signal: i32,
+) -> bool {
+    let handle = match ProcessHandle::open(identity) {
+        Ok(handle) => handle,
+        Err(e) => {
+            app_state.set_status_message(SharedString::from(format!(
+                "Cannot send {} to {name} ({identity}): {e}",
+                signal_name(signal)
+            )));
+            return false;
+        }
+    };
+
+    app_state.set_to_be_signalled_process(SharedString::from(format!("{name} ({identity})")));
+    app_state.set_to_be_sent_signal(SharedString::from(signal_name(signal)));
+    app_state.set_to_be_signalled_tree(ModelRc::default());
+
+    let Ok(mut request_signal_proc) = backend_state.request_signal_proc.write() else {
+        error!("Failed to get write lock on request signal process");
+        return false;
+    };
+    *request_signal_proc = Some((name, handle, signal));
+    if let Ok(mut request_signal_thread) = backend_state.request_signal_thread.write() {
+        *request_signal_thread = None;
+    }
+    true
+}
+
+/// Refreshes the tab of the details pane that is visible, if the pane is open. The handles,
+/// connections and deleted files tabs walk the fds of every process, to find the other
+/// end of pipes, the owners of sockets and the deleted files still open, so the lookups
+/// run off the event loop. A newer refresh cancels the one still running.
+fn refresh_details(ui_handle: slint::Weak<AppWindow>, backend_state: Arc<BackendAppState>) {
+    let Ok(details_open) = backend_state.details_open.read().map(|open| *open) else {
+        error!("Failed to get read lock on details open");
+        return;
+    };
+    if !details_open {
+        return;
+    }
+    let Ok(tab) = backend_state.details_tab.read().map(|tab| *tab) else {
+        error!("Failed to get read lock on details tab");
+        return;
+    };
+    let Ok(selected_proc) = backend_state.selected_proc.read().map(|proc| proc.clone()) else {
+        error!("Failed to get read lock on selected process");
+        return;
+    };
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let Ok(mut running) = backend_state.details_refresh.write() else {
+            error!("Failed to get write lock on details refresh");
+            return;
+        };
+        running.store(true, Ordering::Relaxed);
+        *running = cancelled.clone();
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let Some((message, update)) = details_of(&backend_state, tab, &selected_proc, &cancelled)
+        else {
+            return;
+        };
+
+        let _ = slint::invoke_from_event_loop(move || {
+            if cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+            let Some(app_window) = ui_handle.upgrade() else {
+                error!("Failed to upgrade UI handle");
+                return;
+            };
+            let app_state = AppWindowState::get(&app_window);
+            if let Some(message) = message {
+                app_state.set_details_message(SharedString::from(message));
+            }
+            update.apply(&app_state, &backend_state);
+        });
+    });
+}
+
+/// What a refresh of the details pane shows, one variant per tab.
+enum DetailsUpdate {
+    Handles(Vec<Handle>),
+    MemoryMaps(Vec<MemoryMapping>),
+    Environment(Vec<(String, String)>, String),
+    Credentials(Vec<Vec<StandardListViewItem>>),
+    Connections(Vec<Connection>),
+    DeletedFiles(Vec<DeletedFile>),
+    Outdated(Vec<OutdatedGroup>),
+}
+
+/// Looks up the content of the tab, along with the message about the selected process
+/// for the tabs showing it. Returns `None` when cancelled.
+fn details_of(
+    backend_state: &BackendAppState,
+    tab: DetailsTab,
+    selected_proc: &Option<(String, RowIdentity)>,
+    cancelled: &AtomicBool,
+) -> Option<(Option<String>, DetailsUpdate)> {
+    let read_fd_table = || match FdTable::read(cancelled) {
+        Ok(fd_table) => fd_table,
+        Err(e) => {
+            error!("Failed to walk the open fds: {e}");
+            Some(FdTable::default())
+        }
+    };
+
+    let update = match (tab, selected_proc) {
+        (DetailsTab::Connections, _) => {
+            let mut connections = sockets::connections(&read_fd_table()?).unwrap_or_else(|e| {
+                error!("Failed to list connections: {e}");
+                vec![]
+            });
+            connections.sort_by_cached_key(|connection| {
+                (
+                    connection.socket.protocol(),
+                    connection.socket.local_address(),
+                )
+            });
+            return Some((None, DetailsUpdate::Connections(connections)));
+        }
+        (DetailsTab::DeletedFiles, _) => {
+            let deleted_files = deleted_files::deleted_files(&read_fd_table()?);
+            return Some((None, DetailsUpdate::DeletedFiles(deleted_files)));
+        }
+        (DetailsTab::Outdated, _) => {
+            let Ok(proc_list) = backend_state.curr_proc_list.read() else {
+                error!("Failed to get read lock on current process list");
+                return None;
+            };
+            let groups = group_by_file(proc_list.iter().map(|(proc, _)| proc));
+            return Some((None, DetailsUpdate::Outdated(groups)));
+        }
+        (DetailsTab::Handles, None) => DetailsUpdate::Handles(vec![]),
+        (DetailsTab::MemoryMaps, None) => DetailsUpdate::MemoryMaps(vec![]),
+        (DetailsTab::Environment, None) => DetailsUpdate::Environment(vec![], String::new()),
+        (DetailsTab::Credentials, None) => DetailsUpdate::Credentials(vec![]),
+        (tab, Some((name, row))) => {
+            let (mut message, update) = selected_details_of(tab, name, *row, read_fd_table)?;
+            // why the process matched the `@file` search
+            let file_uses = listed_row(backend_state, *row)
+                .map(|proc| proc.file_uses)
+                .unwrap_or_default();
+            if !file_uses.is_empty() {
+                let uses = file_uses
+                    .iter()
+                    .map(|file_use| format!("{} ({})", file_use.path.display(), file_use.reason))
+                    .collect::<Vec<_>>();
+                message.push_str(&format!(", uses {}", uses.join(", ")));
+            }
+            return Some((Some(message), update));
+        }
+    };
+    Some((Some("Select a process to inspect it".to_string()), update))
+}
+
+/// The tabs about the selected process.
+fn selected_details_of(
+    tab: DetailsTab,
+    name: &str,
+    row: RowIdentity,
+    read_fd_table: impl FnOnce() -> Option<FdTable>,
+) -> Option<(String, DetailsUpdate)> {
+    let identity = row.identity;
+    Some(match tab {
+        DetailsTab::Handles => match handles_of(identity, &read_fd_table()?) {
+            Ok(handles) => (
+                format!("{name} ({identity}) has {} open handles", handles.len()),
+                DetailsUpdate::Handles(handles),
+            ),
+            Err(e) => (
+                format!("Cannot inspect {name} ({identity}): {e}"),
+                DetailsUpdate::Handles(vec![]),
+            ),
+        },
+        DetailsTab::MemoryMaps => match memory_maps_of(identity) {
+            Ok(mappings) => (
+                format!("{name} ({identity}) has {} memory mappings", mappings.len()),
+                DetailsUpdate::MemoryMaps(mappings),
+            ),
+            Err(e) => (
+                format!("Cannot inspect {name} ({identity}): {e}"),
+                DetailsUpdate::MemoryMaps(vec![]),
+            ),
+        },
+        // unlike the rest, the environment is private to the owner of the process
+        DetailsTab::Environment => match environment_of(identity) {
+            Ok(environment) => (
+                format!("{name} ({identity}) has {} variables", environment.len()),
+                DetailsUpdate::Environment(environment, String::new()),
+            ),
+            Err(e) if e.is_permission_denied() => (
+                format!("{name} ({identity})"),
+                DetailsUpdate::Environment(
+                    vec![],
+                    format!(
+                        "Permission denied: the environment of {name} ({identity}) can only be read by its owner or root"
+                    ),
+                ),
+            ),
+            Err(e) => (
+                format!("{name} ({identity})"),
+                DetailsUpdate::Environment(
+                    vec![],
+                    format!("Cannot read the environment of {name} ({identity}): {e}"),
+                ),
+            ),
+        },
+        _ => match credentials_of(identity) {
+            Ok(credentials) => (
+                format!("{name} ({identity})"),
+                DetailsUpdate::Credentials(credentials.to_standard_list_view_items()),
+            ),
+            Err(e) => (
+                format!("Cannot read the credentials of {name} ({identity}): {e}"),
+                DetailsUpdate::Credentials(vec![]),
+            ),
+        },
+    })
+}
+
+impl DetailsUpdate {
+    fn apply(self, app_state: &AppWindowState, backend_state: &BackendAppState) {
+        match self {
+            Self::Handles(handles) => app_state.set_handles(table_model(
+                handles
+                    .iter()
+                    .map(Handle::to_standard_list_view_items)
+                    .collect(),
+            )),
+            Self::MemoryMaps(mappings) => {
+                app_state.set_memory_maps(table_model(
+                    mappings
+                        .iter()
+                        .map(MemoryMapping::to_standard_list_view_items)
+                        .collect(),
+                ));
+                app_state.set_memory_map_groups(table_model(
+                    group_by_backing(&mappings)
+                        .iter()
+                        .map(MappingGroup::to_standard_list_view_items)
+                        .collect(),
+                ));
+            }
+            Self::Environment(environment, error) => {
+                app_state.set_environment(table_model(
+                    environment
+                        .iter()
+                        .map(environment::to_standard_list_view_items)
+                        .collect(),
+                ));
+                app_state.set_environment_error(SharedString::from(error));
+            }
+            Self::Credentials(credentials) => app_state.set_credentials(table_model(credentials)),
+            Self::Connections(connections) => app_state.set_connections(table_model(
+                connections
+                    .iter()
+                    .map(Connection::to_standard_list_view_items)
+                    .collect(),
+            )),
+            Self::DeletedFiles(deleted_files) => {
+                app_state.set_deleted_files_summary(SharedString::from(format!(
+                    "{} deleted files still hold {}",
+                    deleted_files.len(),
+                    human_readable_byte(deleted_files.iter().map(|file| file.size).sum())
+                )));
+                app_state.set_deleted_files(table_model(
+                    deleted_files
+                        .iter()
+                        .map(DeletedFile::to_standard_list_view_items)
+                        .collect(),
+                ));
+                // the sort by size moves the selected file to another row
+                let Ok(mut selected) = backend_state.selected_deleted_file.write() else {
+                    error!("Failed to get write lock on selected deleted file");
+                    return;
+                };
+                let row = selected.and_then(|key| {
+                    deleted_files
+                        .iter()
+                        .position(|deleted_file| deleted_file.key() == key)
+                });
+                if row.is_none() {
+                    *selected = None;
+                }
+                app_state.set_deleted_files_row(row.map_or(-1, |row| row as i32));
+                // the selection is looked up in the list the rows were made from
+                match backend_state.deleted_files.write() {
+                    Ok(mut listed) => *listed = deleted_files,
+                    Err(_) => error!("Failed to get write lock on deleted files"),
+                }
+            }
+            Self::Outdated(groups) => app_state.set_outdated_files(table_model(
+                groups
+                    .iter()
+                    .map(OutdatedGroup::to_standard_list_view_items)
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// The deleted file selected in the details pane, as of the latest refresh.
+fn selected_deleted_file(backend_state: &BackendAppState) -> Option<DeletedFile> {
+    let Ok(selected) = backend_state.selected_deleted_file.read() else {
+        error!("Failed to get read lock on selected deleted file");
+        return None;
+    };
+    let key = (*selected)?;
+    let Ok(deleted_files) = backend_state.deleted_files.read() else {
+        error!("Failed to get read lock on deleted files");
+        return None;
+    };
+    deleted_files
+        .iter()
+        .find(|deleted_file| deleted_file.key() == key)
+        .cloned()
+}
+
+/// Every column but the security label, which is mostly `unconfined` or `none` outside
+/// of servers.
+fn default_visible_columns() -> Vec<usize> {
+    COLUMN_TITLES
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| !matches!(column, Column::Label))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The column at this index of the table, counting only the shown columns.
+fn visible_column(backend_state: &BackendAppState, index: i32) -> Option<Column> {
+    let Ok(visible_columns) = backend_state.visible_columns.read() else {
+        error!("Failed to get read lock on visible columns");
+        return None;
+    };
+    let column = *visible_columns.get(usize::try_from(index).ok()?)?;
+    COLUMN_TITLES.get(column).cloned()
+}
+
+fn show_columns(app_state: &AppWindowState, visible_columns: &[usize]) {
+    let columns = app_state.get_columns();
+    app_state.set_visible_columns(ModelRc::new(VecModel::from(
+        visible_columns
+            .iter()
+            .filter_map(|&column| columns.row_data(column))
+            .collect::<Vec<_>>(),
+    )));
+    app_state.set_column_visible(ModelRc::new(VecModel::from(
+        (0..columns.row_count())
+            .map(|column| visible_columns.contains(&column))
+            .collect::<Vec<_>>(),
+    )));
+}
+
+fn table_model(rows: Vec<Vec<StandardListViewItem>>) -> ModelRc<ModelRc<StandardListViewItem>> {
+    ModelRc::new(VecModel::from(
+        rows.into_iter()
+            .map(|cells| ModelRc::new(VecModel::from(cells)))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// The listed process or thread of a row, as of the last scan.
+fn listed_row(backend_state: &BackendAppState, row: RowIdentity) -> Option<MyProcess> {
+    let Ok(proc_list) = backend_state.curr_proc_list.read() else {
+        error!("Failed to get read lock on current process list");
+        return None;
+    };
+    proc_list
+        .iter()
+        .find(|(proc, _)| proc.row_identity() == row)
+        .map(|(proc, _)| proc.clone())
+}
+
+/// Opens the process of a row, the one the thread belongs to on a thread row, along with
+/// the description of the row for the status messages.
+fn open_row(
+    backend_state: &BackendAppState,
+    name: &str,
+    row: RowIdentity,
+) -> (String, Result<ProcessHandle, ActionError>) {
+    match row.thread_of {
+        Some(process_id) => (
+            format!("{name} (thread {} of {process_id})", row.identity),
+            process_identity_of(backend_state, process_id)
+                .ok_or(ActionError::Identity(IdentityError::Exited))
+                .and_then(ProcessHandle::open),
+        ),
+        None => (
+            format!("{name} ({})", row.identity),
+            ProcessHandle::open(row.identity),
+        ),
+    }
+}
+
+/// Remembers an action that was denied so the user can retry it through the helper.
+fn offer_admin_retry(
+    backend_state: &BackendAppState,
+    app_state: &AppWindowState,
+    description: String,
+    request: HelperRequest,
+) {
+    let Ok(mut admin_retry) = backend_state.admin_retry.write() else {
+        error!("Failed to get write lock on administrator retry");
+        return;
+    };
+    app_state.set_admin_retry_action(SharedString::from(description.clone()));
+    *admin_retry = Some((description, request));
 }
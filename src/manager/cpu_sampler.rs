@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use procfs::{CpuInfo, Current, ticks_per_second};
+
+use crate::manager::{interval_sampler::IntervalSampler, process_identity::ProcessIdentity};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuUsage {
+    /// Percentage of a single core, exceeds 100% for processes using several cores.
+    pub per_core: f32,
+    /// Percentage of the whole machine, between 0% and 100%.
+    pub machine: f32,
+}
+
+/// Remembers the CPU ticks of every process between two `get_sorted_process_list`
+/// calls to report the usage over the refresh interval.
+#[derive(Debug, Clone)]
+pub struct CpuSampler {
+    sampler: IntervalSampler<u64, CpuUsage>,
+    ticks_per_second: f32,
+    num_cores: f32,
+}
+
+impl Default for CpuSampler {
+    fn default() -> Self {
+        Self::new(
+            ticks_per_second() as f32,
+            CpuInfo::current().map_or(1, |info| info.num_cores()) as f32,
+        )
+    }
+}
+
+impl CpuSampler {
+    pub fn new(ticks_per_second: f32, num_cores: f32) -> Self {
+        Self {
+            sampler: IntervalSampler::new(f64::from(ticks_per_second)),
+            ticks_per_second: ticks_per_second.max(1.0),
+            num_cores: num_cores.max(1.0),
+        }
+    }
+
+    /// Must be called once before sampling the processes of a new scan.
+    pub fn begin_tick(&mut self) {
+        self.sampler.begin_tick();
+    }
+
+    /// `total_ticks` is the accumulated user + system time of the process in clock ticks
+    /// as found in `/proc/<pid>/stat`.
+    pub fn sample(&mut self, identity: ProcessIdentity, total_ticks: u64) -> CpuUsage {
+        let (ticks_per_second, num_cores) = (self.ticks_per_second, self.num_cores);
+        self.sampler
+            .sample(identity, total_ticks, |current, previous, elapsed| {
+                usage(
+                    current.saturating_sub(previous),
+                    elapsed,
+                    ticks_per_second,
+                    num_cores,
+                )
+            })
+    }
+
+    /// Forgets the processes that were not sampled during the current tick.
+    pub fn finish_tick(&mut self) {
+        self.sampler.finish_tick();
+    }
+}
+
+fn usage(delta_ticks: u64, elapsed: Duration, ticks_per_second: f32, num_cores: f32) -> CpuUsage {
+    let elapsed_seconds = elapsed.as_secs_f32();
+    if elapsed_seconds <= 0.0 {
+        return CpuUsage::default();
+    }
+
+    let per_core =
+        (delta_ticks as f32 / ticks_per_second / elapsed_seconds * 100.0).min(num_cores * 100.0);
+
+    CpuUsage {
+        per_core,
+        machine: per_core / num_cores,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    fn identity(pid: i32, start_ticks: u64) -> ProcessIdentity {
+        ProcessIdentity { pid, start_ticks }
+    }
+
+    #[test]
+    fn test_cpu_sampler_interval_usage() {
+        let mut sampler = CpuSampler::new(100.0, 4.0);
+        let start = Instant::now();
+
+        sampler.sampler.begin_tick_at(start, None);
+        assert_eq!(
+            sampler.sample(identity(1, 10), 1_000_000),
+            CpuUsage::default()
+        );
+        sampler.finish_tick();
+
+        // 200 ticks over 2 seconds at 100 ticks/s is a full core
+        sampler
+            .sampler
+            .begin_tick_at(start + Duration::from_secs(2), None);
+        let usage = sampler.sample(identity(1, 10), 1_000_200);
+        assert_eq!(usage.per_core, 100.0);
+        assert_eq!(usage.machine, 25.0);
+        sampler.finish_tick();
+
+        // a refresh right after the previous one reports the previous reading
+        sampler
+            .sampler
+            .begin_tick_at(start + Duration::from_millis(2100), None);
+        assert_eq!(sampler.sample(identity(1, 10), 1_000_210), usage);
+        sampler.finish_tick();
+
+        // the baseline was kept, so 100 ticks over 4 seconds is a quarter of a core
+        sampler
+            .sampler
+            .begin_tick_at(start + Duration::from_secs(6), None);
+        assert_eq!(sampler.sample(identity(1, 10), 1_000_300).per_core, 25.0);
+        sampler.finish_tick();
+    }
+
+    #[test]
+    fn test_cpu_sampler_new_and_reused_pid() {
+        let mut sampler = CpuSampler::new(100.0, 2.0);
+        let start = Instant::now();
+
+        sampler.sampler.begin_tick_at(start, None);
+        sampler.sample(identity(1, 10), 5_000);
+        sampler.finish_tick();
+
+        // same PID with a different start time is a new process that has lived for 1 second
+        sampler.sampler.begin_tick_at(
+            start + Duration::from_secs(3),
+            Some(Duration::from_secs(101)),
+        );
+        let usage = sampler.sample(identity(1, 10_000), 50);
+        assert_eq!(usage.per_core, 50.0);
+        assert_eq!(usage.machine, 25.0);
+        sampler.finish_tick();
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use procfs::process::{Process, Status};
+use slint::StandardListViewItem;
+
+use crate::{
+    manager::{process_handle::ActionError, process_identity::ProcessIdentity},
+    utils::capabilities::describe_capabilities,
+};
+
+/// The four IDs the kernel checks a process against, they only differ for setuid
+/// programs and services switching users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ids {
+    pub real: u32,
+    pub effective: u32,
+    pub saved: u32,
+    pub filesystem: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeccompMode {
+    Disabled,
+    Strict,
+    Filter,
+}
+
+impl fmt::Display for SeccompMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "disabled"),
+            Self::Strict => write!(f, "strict"),
+            Self::Filter => write!(f, "filter"),
+        }
+    }
+}
+
+/// Who a process acts as and what it is allowed to do, from `/proc/<pid>/status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub uid: Ids,
+    pub gid: Ids,
+    pub groups: Vec<u32>,
+    pub effective_capabilities: u64,
+    pub permitted_capabilities: u64,
+    /// `None` on kernels older than 2.6.26.
+    pub bounding_capabilities: Option<u64>,
+    /// `None` on kernels older than 4.10.
+    pub no_new_privs: Option<bool>,
+    /// `None` on kernels built without seccomp.
+    pub seccomp: Option<SeccompMode>,
+}
+
+impl From<&Status> for Credentials {
+    fn from(status: &Status) -> Self {
+        Self {
+            uid: Ids {
+                real: status.ruid,
+                effective: status.euid,
+                saved: status.suid,
+                filesystem: status.fuid,
+            },
+            gid: Ids {
+                real: status.rgid,
+                effective: status.egid,
+                saved: status.sgid,
+                filesystem: status.fgid,
+            },
+            groups: status.groups.iter().map(|&group| group as u32).collect(),
+            effective_capabilities: status.capeff,
+            permitted_capabilities: status.capprm,
+            bounding_capabilities: status.capbnd,
+            no_new_privs: status.nonewprivs.map(|flag| flag != 0),
+            seccomp: status.seccomp.and_then(|mode| match mode {
+                0 => Some(SeccompMode::Disabled),
+                1 => Some(SeccompMode::Strict),
+                2 => Some(SeccompMode::Filter),
+                _ => None,
+            }),
+        }
+    }
+}
+
+impl Credentials {
+    /// The name/value rows of the credentials in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<Vec<StandardListViewItem>> {
+        let ids = |ids: Ids, name_of: fn(u32) -> String| {
+            [ids.real, ids.effective, ids.saved, ids.filesystem]
+                .map(|id| format!("{id} ({})", name_of(id)))
+                .join(" / ")
+        };
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "N/A".to_string());
+
+        [
+            (
+                "UIDs (real / effective / saved / fs)",
+                ids(self.uid, user_name),
+            ),
+            (
+                "GIDs (real / effective / saved / fs)",
+                ids(self.gid, group_name),
+            ),
+            (
+                "Supplementary groups",
+                self.groups
+                    .iter()
+                    .map(|&group| format!("{group} ({})", group_name(group)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            (
+                "Effective capabilities",
+                describe_capabilities(self.effective_capabilities),
+            ),
+            (
+                "Permitted capabilities",
+                describe_capabilities(self.permitted_capabilities),
+            ),
+            (
+                "Bounding capabilities",
+                optional(self.bounding_capabilities.map(describe_capabilities)),
+            ),
+            (
+                "NoNewPrivs",
+                optional(self.no_new_privs.map(|flag| flag.to_string())),
+            ),
+            (
+                "Seccomp",
+                optional(self.seccomp.map(|mode| mode.to_string())),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, value)| {
+            vec![
+                StandardListViewItem::from(name),
+                StandardListViewItem::from(value.as_str()),
+            ]
+        })
+        .collect()
+    }
+}
+
+/// The current credentials of the process, they may have changed since the last scan.
+pub fn credentials_of(identity: ProcessIdentity) -> Result<Credentials, ActionError> {
+    identity.verify()?;
+    Ok(Credentials::from(&Process::new(identity.pid)?.status()?))
+}
+
+pub fn user_name(uid: u32) -> String {
+    uzers::get_user_by_uid(uid).map_or_else(
+        || "unknown".to_string(),
+        |user| user.name().to_string_lossy().to_string(),
+    )
+}
+
+fn group_name(gid: u32) -> String {
+    uzers::get_group_by_gid(gid).map_or_else(
+        || "unknown".to_string(),
+        |group| group.name().to_string_lossy().to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_of_myself() {
+        let myself = Process::myself().unwrap();
+        let identity = ProcessIdentity {
+            pid: myself.pid(),
+            start_ticks: myself.stat().unwrap().starttime,
+        };
+
+        let credentials = credentials_of(identity).unwrap();
+        // SAFETY: getuid and geteuid cannot fail
+        let (uid, euid) = unsafe { (libc::getuid(), libc::geteuid()) };
+        assert_eq!(credentials.uid.real, uid);
+        assert_eq!(credentials.uid.effective, euid);
+        assert_eq!(credentials.to_standard_list_view_items().len(), 8);
+    }
+}
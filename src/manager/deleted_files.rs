@@ -0,0 +1,155 @@
+use std::{fs, os::unix::fs::MetadataExt, path::PathBuf};
+
+use procfs::process::FDTarget;
+use slint::StandardListViewItem;
+use task_manager_rs_helper::protocol::HelperAction;
+
+use crate::{
+    manager::{
+        fd_table::FdTable,
+        process_handle::{ActionError, ProcessHandle},
+        process_identity::ProcessIdentity,
+    },
+    utils::human_readable_byte::human_readable_byte,
+};
+
+/// A file that was unlinked while a process still holds it open, its blocks are only
+/// freed once the last fd is closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletedFile {
+    pub owner: ProcessIdentity,
+    pub owner_name: String,
+    pub fd: i32,
+    /// The path the file had, without the ` (deleted)` the kernel appends.
+    pub path: PathBuf,
+    pub size: u64,
+    dev: u64,
+    ino: u64,
+}
+
+/// Tells a deleted file apart from the one that takes its row, or its fd, after a refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeletedFileKey {
+    owner: ProcessIdentity,
+    fd: i32,
+    dev: u64,
+    ino: u64,
+}
+
+impl DeletedFile {
+    pub fn key(&self) -> DeletedFileKey {
+        DeletedFileKey {
+            owner: self.owner,
+            fd: self.fd,
+            dev: self.dev,
+            ino: self.ino,
+        }
+    }
+
+    /// Frees the blocks of the file without closing it, see
+    /// [`ProcessHandle::truncate_file`].
+    pub fn truncate(&self) -> Result<(), ActionError> {
+        ProcessHandle::open(self.owner)?.truncate_file(self.fd, self.dev, self.ino)
+    }
+
+    /// The same truncation, performed by the helper on an administrator retry.
+    pub fn truncate_action(&self) -> HelperAction {
+        HelperAction::Truncate {
+            fd: self.fd,
+            dev: self.dev,
+            ino: self.ino,
+        }
+    }
+
+    /// The cells of the row of the file in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<StandardListViewItem> {
+        [
+            self.owner_name.clone(),
+            self.owner.pid.to_string(),
+            self.fd.to_string(),
+            human_readable_byte(self.size),
+            self.path.display().to_string(),
+        ]
+        .into_iter()
+        .map(|cell| StandardListViewItem::from(cell.as_str()))
+        .collect()
+    }
+}
+
+/// The deleted files held open by the processes of the table, largest first.
+pub fn deleted_files(fd_table: &FdTable) -> Vec<DeletedFile> {
+    let mut deleted = vec![];
+
+    for process in fd_table.processes() {
+        let pid = process.identity.pid;
+        for fd in &process.fds {
+            // memfds are never linked anywhere, they are listed as their own target
+            let FDTarget::Path(path) = &fd.target else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(format!("/proc/{pid}/fd/{}", fd.fd)) else {
+                continue;
+            };
+            // the suffix alone could be part of a real file name
+            if !metadata.is_file() || metadata.nlink() != 0 {
+                continue;
+            }
+            let path = path.to_string_lossy();
+            deleted.push(DeletedFile {
+                owner: process.identity,
+                owner_name: process.name.clone(),
+                fd: fd.fd,
+                path: PathBuf::from(path.strip_suffix(" (deleted)").unwrap_or(&path)),
+                size: metadata.len(),
+                dev: metadata.dev(),
+                ino: metadata.ino(),
+            });
+        }
+    }
+
+    deleted.sort_by_key(|file| std::cmp::Reverse(file.size));
+    deleted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+
+    #[test]
+    fn test_find_and_truncate_deleted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("held.log");
+        std::fs::write(&file, vec![b'x'; 4096]).unwrap();
+
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("exec 5< {}; exec sleep 10", file.display()))
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for process to open fd
+        std::fs::remove_file(&file).unwrap();
+
+        let fd_table = FdTable::read(&AtomicBool::new(false)).unwrap().unwrap();
+        let deleted = deleted_files(&fd_table)
+            .into_iter()
+            .find(|deleted| deleted.owner.pid == child.id() as i32)
+            .expect("The deleted file should be listed");
+        assert_eq!(deleted.fd, 5);
+        assert_eq!(deleted.path, file);
+        assert_eq!(deleted.size, 4096);
+
+        assert_eq!(deleted.truncate(), Ok(()));
+        assert_eq!(
+            fs::metadata(format!("/proc/{}/fd/5", deleted.owner.pid))
+                .unwrap()
+                .len(),
+            0
+        );
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
@@ -0,0 +1,88 @@
+use std::{collections::HashMap, ffi::OsString};
+
+use procfs::{ProcResult, process::Process};
+use slint::StandardListViewItem;
+
+use crate::{
+    manager::{MyProcessID, process_handle::ActionError, process_identity::ProcessIdentity},
+    utils::parse_search_query::EnvQuery,
+};
+
+/// The environment the process was started with, sorted by name. Changes the process
+/// made to its own environment since are not visible.
+pub fn environment_of(identity: ProcessIdentity) -> Result<Vec<(String, String)>, ActionError> {
+    identity.verify()?;
+    let mut environment = Process::new(identity.pid)?
+        .environ()?
+        .into_iter()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    environment.sort();
+    Ok(environment)
+}
+
+/// The cells of the row of a variable in the details pane.
+pub fn to_standard_list_view_items((key, value): &(String, String)) -> Vec<StandardListViewItem> {
+    vec![
+        StandardListViewItem::from(key.as_str()),
+        StandardListViewItem::from(value.as_str()),
+    ]
+}
+
+impl EnvQuery<'_> {
+    pub fn matches(&self, environment: &HashMap<OsString, OsString>) -> bool {
+        let Some(value) = environment.get(&OsString::from(self.key)) else {
+            return false;
+        };
+        self.value.is_none_or(|searched| {
+            value
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(&searched.to_lowercase())
+        })
+    }
+
+    /// Whether the process has the variable, `Err` when its environment cannot be read,
+    /// i.e. for processes of other users.
+    pub fn matches_process(&self, pid: MyProcessID) -> ProcResult<bool> {
+        let environment = Process::new(pid)?.environ()?;
+        Ok(self.matches(&environment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse_search_query::parse_env_query;
+
+    #[test]
+    fn test_env_query_matches_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .env("TASK_MANAGER_TEST", "Debug,Trace")
+            .spawn()
+            .expect("Failed to spawn process");
+        let pid = child.id() as MyProcessID;
+
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for the exec
+
+        let matches = |query| {
+            parse_env_query(query)
+                .unwrap()
+                .matches_process(pid)
+                .unwrap()
+        };
+        assert!(matches("TASK_MANAGER_TEST"));
+        assert!(matches("TASK_MANAGER_TEST=debug"));
+        assert!(!matches("TASK_MANAGER_TEST=info"));
+        assert!(!matches("task_manager_test"));
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
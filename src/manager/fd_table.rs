@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use procfs::{ProcResult, process::FDInfo};
+
+use crate::manager::{process_identity::ProcessIdentity, traits::process_name::ProcessName};
+
+/// The open fds of a process at the time the table was read.
+#[derive(Debug, Clone)]
+pub struct ProcessFds {
+    pub identity: ProcessIdentity,
+    pub name: String,
+    pub fds: Vec<FDInfo>,
+}
+
+/// The fds of every process whose fds can be read, those of other users only when running
+/// as root. Walking them is the most expensive lookup there is, so the pipe ends, the
+/// socket owners and the deleted files are all looked up in the same table.
+#[derive(Debug, Default)]
+pub struct FdTable {
+    processes: Vec<ProcessFds>,
+}
+
+impl FdTable {
+    /// Returns `None` when `cancelled` gets set before every process was walked.
+    pub fn read(cancelled: &AtomicBool) -> ProcResult<Option<Self>> {
+        let mut processes = vec![];
+        for process in procfs::process::all_processes()?.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            let Ok(fds) = process.fd() else {
+                continue;
+            };
+            let Ok(stat) = process.stat() else {
+                continue;
+            };
+            processes.push(ProcessFds {
+                identity: ProcessIdentity {
+                    pid: stat.pid,
+                    start_ticks: stat.starttime,
+                },
+                name: process.process_name(),
+                fds: fds.flatten().collect(),
+            });
+        }
+        Ok(Some(Self { processes }))
+    }
+
+    pub fn processes(&self) -> &[ProcessFds] {
+        &self.processes
+    }
+}
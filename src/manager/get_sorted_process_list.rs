@@ -1,29 +1,56 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
-use procfs::ProcResult;
+use procfs::{ProcError, ProcResult};
 
 use crate::{
     manager::{
-        Column, MyProcess, MyProcessID, SortOrder,
+        Column, FileUse, MyProcess, MyProcessID, SortOrder,
+        credentials::user_name,
+        io_sampler::IoRates,
+        memory_usage::MemoryMetric,
+        outdated_files::OutdatedFile,
+        process_identity::ProcessIdentity,
+        scan_state::ScanState,
         traits::{
+            cpu_percent::CpuPercent, find_outdated_files::FindOutdatedFiles,
+            get_procs_using_file::GetProcsUsingFile, io_rates::SampleIoRates,
             sort_my_processes::SortMyProcesses, to_my_processes::ToMyProcesses,
-            to_root_parents_and_children::ToRootParentsAndChildren,
+            to_my_threads::ToMyThreads, to_root_parents_and_children::ToRootParentsAndChildren,
         },
     },
-    utils::{parse_search_query::parse_search_query, vec_take::VecTake},
+    utils::{
+        capabilities::capability_bit,
+        cpu_list::format_cpu_list,
+        parse_search_query::{parse_env_query, parse_port, parse_search_query},
+        vec_take::VecTake,
+    },
 };
 
 struct MyProcessSortItem {
     pub id: MyProcessID,
     pub level: usize,
+    pub thread_of: Option<MyProcessID>,
 }
 
+/// Returns `None` when `cancelled` gets set before the list is complete. The CPU and I/O
+/// samplers in `scan_state` are only updated by a complete scan, so the next scan still
+/// measures the rates over the whole interval, its caches keep what a cancelled scan read.
+/// The threads of the `expanded` processes are listed right below them.
 pub fn get_sorted_process_list(
     sort_by: &Column,
     sort_order: &SortOrder,
     search_term: &str,
-) -> ProcResult<Vec<(MyProcess, usize)>> {
+    memory_metric: MemoryMetric,
+    expanded: &HashSet<ProcessIdentity>,
+    scan_state: &mut ScanState,
+    cancelled: &AtomicBool,
+) -> ProcResult<Option<Vec<(MyProcess, usize)>>> {
     let searches = parse_search_query(search_term);
+    let thread_sort_order = sort_order;
 
     // reverse the sort order so we don't have to re-reverse when building the tree
     let sort_order = match sort_order {
@@ -31,17 +58,103 @@ pub fn get_sorted_process_list(
         SortOrder::Descending => &SortOrder::Ascending,
     };
 
-    let mut my_processes = procfs::process::all_processes()?.to_my_processes();
+    let Some(mut my_processes) = procfs::process::all_processes()?.to_my_processes(cancelled)
+    else {
+        return Ok(None);
+    };
+    // sampled into copies, committed once the scan can no longer be cancelled
+    let (mut cpu, mut io, mut thread_cpu) = (
+        scan_state.cpu.clone(),
+        scan_state.io.clone(),
+        scan_state.thread_cpu.clone(),
+    );
+    my_processes.sample_cpu_percent(&mut cpu);
+    my_processes.sample_io_rates(&mut io);
+    my_processes.find_outdated_files(&mut scan_state.outdated_files);
+    scan_state
+        .open_files
+        .retain(&my_processes.iter().map(MyProcess::identity).collect());
+
+    let mut threads = my_processes
+        .iter()
+        .filter(|proc| expanded.contains(&proc.identity()))
+        .flat_map(|proc| proc.to_my_threads())
+        .collect::<Vec<_>>();
+    threads.sample_cpu_percent(&mut thread_cpu);
+    let mut threads_by_process = HashMap::<MyProcessID, Vec<&MyProcess>>::new();
+    for thread in &threads {
+        if let Some(process_id) = thread.thread_of {
+            threads_by_process
+                .entry(process_id)
+                .or_default()
+                .push(thread);
+        }
+    }
+    // threads are listed as they are sorted, not pushed onto the stack below
+    for process_threads in threads_by_process.values_mut() {
+        process_threads.sort(sort_by, thread_sort_order, memory_metric);
+    }
+    let threads_by_process = threads_by_process
+        .into_iter()
+        .map(|(process_id, process_threads)| {
+            let thread_ids = process_threads
+                .iter()
+                .map(|thread| thread.id)
+                .collect::<Vec<_>>();
+            (process_id, thread_ids)
+        })
+        .collect::<HashMap<_, _>>();
     let (mut root_parents, mut flatten_children) = my_processes.to_root_parents_and_children();
 
+    let mut unreadable_environments = HashSet::new();
+    let mut file_uses = HashMap::<MyProcessID, Vec<FileUse>>::new();
     if !searches.is_empty() {
         for search in searches {
+            let search_col = search.column.to_lowercase();
+            // an absolute path is looked up like fuser does and a port like `ss -p` does,
+            // anything else is a substring
+            let exact_matches = match search_col.as_str() {
+                "file" if Path::new(search.value).is_absolute() => {
+                    Path::new(search.value).procs_using_file().ok().map(|uses| {
+                        let pids = uses
+                            .iter()
+                            .map(|file_use| file_use.pid)
+                            .collect::<HashSet<_>>();
+                        for file_use in uses {
+                            file_uses.entry(file_use.pid).or_default().push(file_use);
+                        }
+                        pids
+                    })
+                }
+                // `@port` finds the servers on the port, `@rport` their clients
+                "port" | "rport" => Some(
+                    parse_port(search.value)
+                        .and_then(|port| {
+                            scan_state
+                                .socket_owners
+                                .procs_using_port(port, search_col == "rport", cancelled)
+                                .ok()?
+                        })
+                        .into_iter()
+                        .flatten()
+                        .collect::<HashSet<_>>(),
+                ),
+                _ => None,
+            };
+            let env_query = (search_col == "env")
+                .then(|| parse_env_query(search.value))
+                .flatten();
+
             let mut retain_proc_ids = my_processes
                 .iter()
                 .filter_map(|proc| {
-                    let search_col = search.column.to_lowercase();
+                    if let Some(exact_matches) = &exact_matches {
+                        return exact_matches.contains(&proc.id).then_some(proc.id);
+                    }
                     if search_col == "file" {
-                        if proc.files_using.iter().any(|file| {
+                        // only read when a file is searched for
+                        let files_using = scan_state.open_files.files_of(proc.identity());
+                        if files_using.iter().any(|file| {
                             file.to_string_lossy()
                                 .to_lowercase()
                                 .contains(&search.value.to_lowercase())
@@ -52,10 +165,58 @@ pub fn get_sorted_process_list(
                         };
                     }
 
+                    if search_col == "env" {
+                        let env_query = env_query.as_ref()?;
+                        return match env_query.matches_process(proc.id) {
+                            Ok(matches) => matches.then_some(proc.id),
+                            Err(ProcError::PermissionDenied(_)) => {
+                                unreadable_environments.insert(proc.id);
+                                None
+                            }
+                            Err(_) => None,
+                        };
+                    }
+
+                    if search_col == "cap" {
+                        let bit = capability_bit(search.value)?;
+                        let credentials = proc.credentials.as_ref()?;
+                        return (credentials.effective_capabilities & (1 << bit) != 0)
+                            .then_some(proc.id);
+                    }
+
                     let a = match search_col.as_str() {
                         "id" => proc.id.to_string(),
                         "cpu" => proc.cpu_percent.to_string(),
-                        "memory" => proc.memory_bytes.to_string(),
+                        "cputotal" => proc.cpu_total_percent.to_string(),
+                        "lastcpu" => proc
+                            .last_cpu
+                            .map_or_else(|| "N/A".to_string(), |cpu| cpu.to_string()),
+                        "affinity" => proc
+                            .allowed_cpus
+                            .as_deref()
+                            .map_or_else(|| "N/A".to_string(), format_cpu_list),
+                        "threads" => proc.threads.to_string(),
+                        "priority" => proc.priority.to_string(),
+                        "nice" => proc.nice.to_string(),
+                        "policy" => proc
+                            .sched_policy
+                            .map_or_else(|| "N/A".to_string(), |policy| policy.to_string()),
+                        "iopriority" | "ioprio" => proc
+                            .io_priority
+                            .map_or_else(|| "N/A".to_string(), |priority| priority.to_string()),
+                        "memory" => optional_bytes(proc.memory.get(memory_metric)),
+                        "rss" => proc.memory.rss.to_string(),
+                        "pss" => optional_bytes(proc.memory.pss),
+                        "uss" => optional_bytes(proc.memory.uss),
+                        "shared" => proc.memory.shared.to_string(),
+                        "swap" => optional_bytes(proc.memory.swap),
+                        "diskread" => io_rate(proc.io_rates, |rates| rates.read_bytes),
+                        "diskwrite" => io_rate(proc.io_rates, |rates| rates.write_bytes),
+                        "readcalls" => io_rate(proc.io_rates, |rates| rates.read_calls),
+                        "writecalls" => io_rate(proc.io_rates, |rates| rates.write_calls),
+                        "cancelledwrites" => {
+                            io_rate(proc.io_rates, |rates| rates.cancelled_write_bytes)
+                        }
                         "parentid" => proc.parent_id.to_string(),
                         "state" => format!("{:?}", proc.state),
                         "starttime" => proc
@@ -63,6 +224,15 @@ pub fn get_sorted_process_list(
                             .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
                             .unwrap_or_else(|| "N/A".to_string()),
                         "user" => proc.user.clone(),
+                        "euid" => proc.credentials.as_ref().map_or_else(
+                            || "N/A".to_string(),
+                            |credentials| {
+                                let euid = credentials.uid.effective;
+                                format!("{euid} {}", user_name(euid))
+                            },
+                        ),
+                        "outdated" => outdated(proc.outdated_files.as_deref()),
+                        "label" => proc.security_label.to_string(),
                         "command" => proc.command.clone(),
                         _ => proc.name.clone(),
                     };
@@ -111,9 +281,15 @@ pub fn get_sorted_process_list(
         }
     }
 
-    root_parents.sort(sort_by, sort_order);
+    if cancelled.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    (scan_state.cpu, scan_state.io, scan_state.thread_cpu) = (cpu, io, thread_cpu);
+    scan_state.unreadable_environments = unreadable_environments.len();
+
+    root_parents.sort(sort_by, sort_order, memory_metric);
     for fc in flatten_children.values_mut() {
-        fc.sort(sort_by, sort_order);
+        fc.sort(sort_by, sort_order, memory_metric);
     }
 
     let mut process_tree = vec![];
@@ -123,6 +299,7 @@ pub fn get_sorted_process_list(
         stacks.push(MyProcessSortItem {
             id: root.id,
             level: 0,
+            thread_of: None,
         });
     }
 
@@ -131,24 +308,147 @@ pub fn get_sorted_process_list(
         let id = node.id;
         process_tree.push(node);
 
+        for thread_id in threads_by_process.get(&id).into_iter().flatten() {
+            process_tree.push(MyProcessSortItem {
+                id: *thread_id,
+                level: level + 1,
+                thread_of: Some(id),
+            });
+        }
+
         if let Some(child_processes) = flatten_children.get(&id) {
             for child in child_processes.iter() {
                 stacks.push(MyProcessSortItem {
                     id: child.id,
                     level: level + 1,
+                    thread_of: None,
                 });
             }
         }
     }
 
-    Ok(process_tree
-        .iter()
-        .filter_map(|item| {
-            my_processes
+    for proc in &mut my_processes {
+        if let Some(uses) = file_uses.remove(&proc.id) {
+            proc.file_uses = uses;
+        }
+    }
+
+    Ok(Some(
+        process_tree
+            .iter()
+            .filter_map(|item| {
+                let rows = match item.thread_of {
+                    Some(_) => &mut threads,
+                    None => &mut my_processes,
+                };
+                rows.iter()
+                    .position(|p| p.id == item.id && p.thread_of == item.thread_of)
+                    .and_then(|index| rows.take(index))
+                    .map(|process| (process, item.level))
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn outdated(outdated_files: Option<&[OutdatedFile]>) -> String {
+    match outdated_files {
+        None => "N/A".to_string(),
+        Some([]) => "no".to_string(),
+        Some(files) => format!("yes ({})", files.len()),
+    }
+}
+
+fn optional_bytes(bytes: Option<u64>) -> String {
+    bytes.map_or_else(|| "N/A".to_string(), |bytes| bytes.to_string())
+}
+
+fn io_rate(rates: Option<IoRates>, rate: fn(IoRates) -> u64) -> String {
+    optional_bytes(rates.map(rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::FileUseReason;
+
+    #[test]
+    fn test_threads_listed_below_expanded_process() {
+        let myself = procfs::process::Process::myself().unwrap();
+        let identity = ProcessIdentity {
+            pid: myself.pid(),
+            start_ticks: myself.stat().unwrap().starttime,
+        };
+
+        let processes = get_sorted_process_list(
+            &Column::ID,
+            &SortOrder::Ascending,
+            "",
+            MemoryMetric::default(),
+            &HashSet::from([identity]),
+            &mut ScanState::default(),
+            &AtomicBool::new(false),
+        )
+        .unwrap()
+        .unwrap();
+
+        let row = processes
+            .iter()
+            .position(|(proc, _)| proc.identity() == identity && proc.thread_of.is_none())
+            .expect("The test process should be listed");
+        let level = processes[row].1;
+        let threads = processes[row + 1..]
+            .iter()
+            .take_while(|(proc, _)| proc.thread_of == Some(identity.pid))
+            .collect::<Vec<_>>();
+
+        assert!(!threads.is_empty());
+        assert!(
+            threads
                 .iter()
-                .position(|p| p.id == item.id)
-                .and_then(|index| my_processes.take(index))
-                .map(|process| (process, item.level))
-        })
-        .collect::<Vec<_>>())
+                .all(|(_, thread_level)| *thread_level == level + 1)
+        );
+        assert!(threads.iter().any(|(thread, _)| thread.id == identity.pid));
+    }
+
+    #[test]
+    fn test_file_search_records_uses() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let pid = std::process::id() as MyProcessID;
+
+        let processes = get_sorted_process_list(
+            &Column::ID,
+            &SortOrder::Ascending,
+            &format!("file {}", file.path().display()),
+            MemoryMetric::default(),
+            &HashSet::new(),
+            &mut ScanState::default(),
+            &AtomicBool::new(false),
+        )
+        .unwrap()
+        .unwrap();
+
+        let (myself, _) = processes
+            .iter()
+            .find(|(proc, _)| proc.id == pid)
+            .expect("The test process should match");
+        assert!(
+            myself.file_uses.iter().any(|file_use| matches!(
+                file_use.reason,
+                FileUseReason::Fd {
+                    read: true,
+                    write: true,
+                    ..
+                }
+            )),
+            "Unexpected uses: {:?}",
+            myself.file_uses
+        );
+        // the ancestors are only listed to keep the tree
+        assert!(
+            processes
+                .iter()
+                .filter(|(proc, _)| proc.id != pid)
+                .all(|(proc, _)| proc.file_uses.is_empty())
+        );
+    }
 }
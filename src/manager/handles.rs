@@ -0,0 +1,323 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use procfs::process::{FDInfo, FDPermissions, FDTarget, Process};
+use slint::StandardListViewItem;
+
+use crate::manager::{
+    MyProcessID, fd_table::FdTable, process_handle::ActionError, process_identity::ProcessIdentity,
+    sockets::SocketTable,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    File,
+    Socket,
+    Pipe,
+    /// An eventfd, epoll, timerfd, signalfd or any other inode-less file.
+    AnonInode,
+    MemFd,
+    Net,
+    Other,
+}
+
+impl fmt::Display for HandleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File => write!(f, "file"),
+            Self::Socket => write!(f, "socket"),
+            Self::Pipe => write!(f, "pipe"),
+            Self::AnonInode => write!(f, "anon inode"),
+            Self::MemFd => write!(f, "memfd"),
+            Self::Net => write!(f, "net"),
+            Self::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// An open file descriptor of a process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Handle {
+    pub fd: i32,
+    pub kind: HandleKind,
+    /// Where the fd link points to, e.g. `/etc/hosts` or `pipe:[1234]`.
+    pub target: String,
+    pub fd_info: Option<FdInfo>,
+    /// What is at the other end: the address of a socket, the processes sharing a pipe.
+    pub details: String,
+}
+
+impl Handle {
+    /// The cells of the row of the handle in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<StandardListViewItem> {
+        let (flags, position, mount_id) = match self.fd_info {
+            Some(fd_info) => (
+                fd_info.describe_flags(),
+                fd_info.position.to_string(),
+                fd_info.mount_id.to_string(),
+            ),
+            None => ("N/A".to_string(), "N/A".to_string(), "N/A".to_string()),
+        };
+        [
+            self.fd.to_string(),
+            self.kind.to_string(),
+            self.target.clone(),
+            flags,
+            position,
+            mount_id,
+            self.details.clone(),
+        ]
+        .into_iter()
+        .map(|cell| StandardListViewItem::from(cell.as_str()))
+        .collect()
+    }
+}
+
+/// The part of `/proc/<pid>/fdinfo/<fd>` shared by every kind of file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdInfo {
+    pub position: u64,
+    /// The `open` flags, `O_CLOEXEC` included.
+    pub flags: i32,
+    pub mount_id: i32,
+}
+
+impl FdInfo {
+    fn parse(fdinfo: &str) -> Option<Self> {
+        let field = |name: &str| {
+            fdinfo.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == name).then(|| value.trim())
+            })
+        };
+        Some(Self {
+            position: field("pos")?.parse().ok()?,
+            flags: i32::from_str_radix(field("flags")?, 8).ok()?,
+            mount_id: field("mnt_id")?.parse().ok()?,
+        })
+    }
+
+    /// The access mode followed by the flags worth knowing about.
+    pub fn describe_flags(&self) -> String {
+        let mut flags = vec![match self.flags & libc::O_ACCMODE {
+            libc::O_RDONLY => "read",
+            libc::O_WRONLY => "write",
+            _ => "read/write",
+        }];
+        for (flag, name) in [
+            (libc::O_APPEND, "append"),
+            (libc::O_NONBLOCK, "nonblock"),
+            (libc::O_CLOEXEC, "cloexec"),
+            (libc::O_DIRECT, "direct"),
+            (libc::O_SYNC, "sync"),
+            (libc::O_PATH, "path"),
+        ] {
+            if self.flags & flag == flag {
+                flags.push(name);
+            }
+        }
+        flags.join(" ")
+    }
+}
+
+/// One end of a pipe held by some process.
+#[derive(Debug, Clone)]
+struct PipeEnd {
+    pid: MyProcessID,
+    name: String,
+    fd: i32,
+    mode: FDPermissions,
+}
+
+impl fmt::Display for PipeEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = if self.mode.contains(FDPermissions::WRITE) {
+            "writes"
+        } else {
+            "reads"
+        };
+        write!(f, "{} ({}) {mode} on fd {}", self.name, self.pid, self.fd)
+    }
+}
+
+/// Lists every fd of the process, resolving sockets to their addresses and pipes to the
+/// processes of the table holding their other end.
+pub fn handles_of(
+    identity: ProcessIdentity,
+    fd_table: &FdTable,
+) -> Result<Vec<Handle>, ActionError> {
+    identity.verify()?;
+    let process = Process::new(identity.pid)?;
+    let fds = process.fd()?.flatten().collect::<Vec<_>>();
+
+    let sockets = fds
+        .iter()
+        .any(|fd| matches!(fd.target, FDTarget::Socket(_)))
+        .then(|| SocketTable::of(&process));
+    let pipes = fds
+        .iter()
+        .filter_map(|fd| match fd.target {
+            FDTarget::Pipe(inode) => Some(inode),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    let pipe_ends = pipe_ends(&pipes, fd_table);
+
+    Ok(fds
+        .into_iter()
+        .map(|fd| {
+            let fd_info =
+                std::fs::read_to_string(format!("/proc/{}/fdinfo/{}", identity.pid, fd.fd))
+                    .ok()
+                    .and_then(|fdinfo| FdInfo::parse(&fdinfo));
+            let (kind, target, details) = describe_target(&fd, identity.pid, &sockets, &pipe_ends);
+            Handle {
+                fd: fd.fd,
+                kind,
+                target,
+                fd_info,
+                details,
+            }
+        })
+        .collect())
+}
+
+fn describe_target(
+    fd: &FDInfo,
+    pid: MyProcessID,
+    sockets: &Option<SocketTable>,
+    pipe_ends: &HashMap<u64, Vec<PipeEnd>>,
+) -> (HandleKind, String, String) {
+    match &fd.target {
+        FDTarget::Path(path) => (HandleKind::File, path.display().to_string(), String::new()),
+        FDTarget::Socket(inode) => (
+            HandleKind::Socket,
+            format!("socket:[{inode}]"),
+            sockets
+                .as_ref()
+                .and_then(|sockets| sockets.get(*inode))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        ),
+        FDTarget::Pipe(inode) => (
+            HandleKind::Pipe,
+            format!("pipe:[{inode}]"),
+            pipe_ends
+                .get(inode)
+                .into_iter()
+                .flatten()
+                .filter(|end| end.pid != pid || end.fd != fd.fd)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        FDTarget::AnonInode(name) => (
+            HandleKind::AnonInode,
+            format!("anon_inode:{name}"),
+            String::new(),
+        ),
+        FDTarget::MemFD(name) => (HandleKind::MemFd, format!("memfd:{name}"), String::new()),
+        FDTarget::Net(inode) => (HandleKind::Net, format!("net:[{inode}]"), String::new()),
+        FDTarget::Other(name, inode) => (
+            HandleKind::Other,
+            format!("{name}:[{inode}]"),
+            String::new(),
+        ),
+    }
+}
+
+/// Every process of the table holding one of the pipes.
+fn pipe_ends(pipes: &HashSet<u64>, fd_table: &FdTable) -> HashMap<u64, Vec<PipeEnd>> {
+    let mut ends = HashMap::<u64, Vec<PipeEnd>>::new();
+    if pipes.is_empty() {
+        return ends;
+    }
+
+    for process in fd_table.processes() {
+        for fd in &process.fds {
+            let FDTarget::Pipe(inode) = fd.target else {
+                continue;
+            };
+            if pipes.contains(&inode) {
+                ends.entry(inode).or_default().push(PipeEnd {
+                    pid: process.identity.pid,
+                    name: process.name.clone(),
+                    fd: fd.fd,
+                    mode: fd.mode(),
+                });
+            }
+        }
+    }
+    ends
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_fdinfo() {
+        let fd_info =
+            FdInfo::parse("pos:\t42\nflags:\t02004002\nmnt_id:\t27\nino:\t1234\n").unwrap();
+        assert_eq!(fd_info.position, 42);
+        assert_eq!(fd_info.mount_id, 27);
+        assert_eq!(fd_info.describe_flags(), "read/write nonblock cloexec");
+
+        assert_eq!(FdInfo::parse("flags:\t0\n"), None);
+    }
+
+    #[test]
+    fn test_handles_of_pipe() {
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg("sleep 10 | sleep 10")
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for the pipeline to start
+
+        let shell = Process::new(child.id() as MyProcessID).unwrap();
+        let writer = procfs::process::all_processes()
+            .unwrap()
+            .flatten()
+            .find(|process| {
+                process.stat().is_ok_and(|stat| stat.ppid == shell.pid())
+                    && process.fd().is_ok_and(|mut fds| {
+                        fds.any(|fd| {
+                            fd.is_ok_and(|fd| fd.fd == 1 && matches!(fd.target, FDTarget::Pipe(_)))
+                        })
+                    })
+            })
+            .expect("The writing end of the pipeline should be running");
+
+        let fd_table = FdTable::read(&AtomicBool::new(false)).unwrap().unwrap();
+        let handles = handles_of(
+            ProcessIdentity {
+                pid: writer.pid(),
+                start_ticks: writer.stat().unwrap().starttime,
+            },
+            &fd_table,
+        )
+        .unwrap();
+        let stdout = handles.iter().find(|handle| handle.fd == 1).unwrap();
+        assert_eq!(stdout.kind, HandleKind::Pipe);
+        assert!(
+            stdout.details.contains("reads on fd 0"),
+            "Unexpected pipe details: {}",
+            stdout.details
+        );
+        assert_eq!(
+            stdout
+                .fd_info
+                .map(|fd_info| fd_info.flags & libc::O_ACCMODE),
+            Some(libc::O_WRONLY)
+        );
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
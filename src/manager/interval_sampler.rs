@@ -0,0 +1,171 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use procfs::{Current, Uptime};
+
+use crate::manager::process_identity::ProcessIdentity;
+
+/// Refreshes closer together than this (e.g. while typing a search query) reuse the
+/// previous reading instead of dividing a handful of ticks by a tiny interval.
+pub(crate) const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy)]
+struct Sample<C, R> {
+    counters: C,
+    rate: R,
+    generation: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Tick {
+    /// No previous sample to compare against.
+    First,
+    /// Too soon after the previous sample, report the previous readings.
+    Reuse,
+    Measure {
+        interval: Duration,
+        uptime: Option<Duration>,
+    },
+}
+
+/// Remembers counters that only grow, like CPU ticks or I/O bytes, of every process
+/// between two scans to report them as a rate over the refresh interval. See
+/// [`crate::manager::cpu_sampler::CpuSampler`] and
+/// [`crate::manager::io_sampler::IoSampler`].
+#[derive(Debug, Clone)]
+pub struct IntervalSampler<C, R> {
+    samples: HashMap<ProcessIdentity, Sample<C, R>>,
+    last_measured_at: Option<Instant>,
+    tick: Tick,
+    generation: u64,
+    ticks_per_second: f64,
+}
+
+impl<C: Copy + Default, R: Copy + Default> IntervalSampler<C, R> {
+    pub fn new(ticks_per_second: f64) -> Self {
+        Self {
+            samples: HashMap::new(),
+            last_measured_at: None,
+            tick: Tick::First,
+            generation: 0,
+            ticks_per_second: ticks_per_second.max(1.0),
+        }
+    }
+
+    /// Must be called once before sampling the processes of a new scan.
+    pub fn begin_tick(&mut self) {
+        self.begin_tick_at(
+            Instant::now(),
+            Uptime::current().ok().map(|u| u.uptime_duration()),
+        );
+    }
+
+    pub(crate) fn begin_tick_at(&mut self, now: Instant, uptime: Option<Duration>) {
+        self.generation += 1;
+        self.tick = match self.last_measured_at {
+            None => Tick::First,
+            Some(last) if now.duration_since(last) < MIN_SAMPLE_INTERVAL => Tick::Reuse,
+            Some(last) => Tick::Measure {
+                interval: now.duration_since(last),
+                uptime,
+            },
+        };
+        if !matches!(self.tick, Tick::Reuse) {
+            self.last_measured_at = Some(now);
+        }
+    }
+
+    /// `rate` turns the current and the previous counters into a rate over the elapsed
+    /// time. A process started after the previous sample is compared against the default
+    /// counters over its own lifetime.
+    pub fn sample(
+        &mut self,
+        identity: ProcessIdentity,
+        counters: C,
+        rate: impl FnOnce(C, C, Duration) -> R,
+    ) -> R {
+        let generation = self.generation;
+        let previous = self.samples.get(&identity).copied();
+
+        let rate = match (self.tick, previous) {
+            (Tick::Reuse, Some(previous)) => {
+                self.samples.insert(
+                    identity,
+                    Sample {
+                        generation,
+                        ..previous
+                    },
+                );
+                return previous.rate;
+            }
+            (Tick::First | Tick::Reuse, _) => R::default(),
+            (Tick::Measure { interval, .. }, Some(previous)) => {
+                rate(counters, previous.counters, interval)
+            }
+            (Tick::Measure { interval, uptime }, None) => {
+                let lifetime = uptime
+                    .map(|uptime| {
+                        uptime.saturating_sub(Duration::from_secs_f64(
+                            identity.start_ticks as f64 / self.ticks_per_second,
+                        ))
+                    })
+                    .unwrap_or(interval);
+                rate(counters, C::default(), lifetime.min(interval))
+            }
+        };
+
+        self.samples.insert(
+            identity,
+            Sample {
+                counters,
+                rate,
+                generation,
+            },
+        );
+        rate
+    }
+
+    /// Forgets the processes that were not sampled during the current tick.
+    pub fn finish_tick(&mut self) {
+        let generation = self.generation;
+        self.samples
+            .retain(|_, sample| sample.generation == generation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(pid: i32, start_ticks: u64) -> ProcessIdentity {
+        ProcessIdentity { pid, start_ticks }
+    }
+
+    fn per_second(current: u64, previous: u64, elapsed: Duration) -> f64 {
+        (current - previous) as f64 / elapsed.as_secs_f64()
+    }
+
+    #[test]
+    fn test_interval_sampler_forgets_exited_processes() {
+        let mut sampler = IntervalSampler::<u64, f64>::new(100.0);
+        let start = Instant::now();
+
+        sampler.begin_tick_at(start, None);
+        sampler.sample(identity(1, 10), 5_000, per_second);
+        sampler.sample(identity(2, 10), 5_000, per_second);
+        sampler.finish_tick();
+
+        // same PID with a different start time is a new process that has lived for 1 second
+        sampler.begin_tick_at(
+            start + Duration::from_secs(3),
+            Some(Duration::from_secs(101)),
+        );
+        assert_eq!(sampler.sample(identity(1, 10_000), 50, per_second), 50.0);
+        sampler.finish_tick();
+
+        assert_eq!(sampler.samples.len(), 1);
+        assert!(sampler.samples.contains_key(&identity(1, 10_000)));
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use procfs::{process::Io, ticks_per_second};
+
+use crate::manager::{interval_sampler::IntervalSampler, process_identity::ProcessIdentity};
+
+/// The counters of `/proc/<pid>/io` since the process started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoCounters {
+    /// Bytes actually fetched from the storage layer, page cache hits excluded.
+    pub read_bytes: u64,
+    /// Bytes sent (or about to be sent) to the storage layer.
+    pub write_bytes: u64,
+    pub read_calls: u64,
+    pub write_calls: u64,
+    /// Bytes that were written to the page cache but never reached the storage layer,
+    /// e.g. because the file was truncated or deleted before writeback.
+    pub cancelled_write_bytes: u64,
+}
+
+impl From<Io> for IoCounters {
+    fn from(io: Io) -> Self {
+        Self {
+            read_bytes: io.read_bytes,
+            write_bytes: io.write_bytes,
+            read_calls: io.syscr,
+            write_calls: io.syscw,
+            cancelled_write_bytes: io.cancelled_write_bytes,
+        }
+    }
+}
+
+/// [`IoCounters`] per second over the refresh interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoRates {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_calls: u64,
+    pub write_calls: u64,
+    pub cancelled_write_bytes: u64,
+}
+
+/// Remembers the I/O counters of every process between two scans to report them as
+/// rates, the same way [`crate::manager::cpu_sampler::CpuSampler`] does for CPU ticks.
+#[derive(Debug, Clone)]
+pub struct IoSampler {
+    sampler: IntervalSampler<IoCounters, IoRates>,
+}
+
+impl Default for IoSampler {
+    fn default() -> Self {
+        Self::new(ticks_per_second() as f64)
+    }
+}
+
+impl IoSampler {
+    pub fn new(ticks_per_second: f64) -> Self {
+        Self {
+            sampler: IntervalSampler::new(ticks_per_second),
+        }
+    }
+
+    /// Must be called once before sampling the processes of a new scan.
+    pub fn begin_tick(&mut self) {
+        self.sampler.begin_tick();
+    }
+
+    pub fn sample(&mut self, identity: ProcessIdentity, counters: IoCounters) -> IoRates {
+        self.sampler.sample(identity, counters, rates)
+    }
+
+    /// Forgets the processes that were not sampled during the current tick.
+    pub fn finish_tick(&mut self) {
+        self.sampler.finish_tick();
+    }
+}
+
+fn rates(current: IoCounters, previous: IoCounters, elapsed: Duration) -> IoRates {
+    let elapsed_seconds = elapsed.as_secs_f64();
+    if elapsed_seconds <= 0.0 {
+        return IoRates::default();
+    }
+    let per_second = |current: u64, previous: u64| {
+        (current.saturating_sub(previous) as f64 / elapsed_seconds).round() as u64
+    };
+
+    IoRates {
+        read_bytes: per_second(current.read_bytes, previous.read_bytes),
+        write_bytes: per_second(current.write_bytes, previous.write_bytes),
+        read_calls: per_second(current.read_calls, previous.read_calls),
+        write_calls: per_second(current.write_calls, previous.write_calls),
+        cancelled_write_bytes: per_second(
+            current.cancelled_write_bytes,
+            previous.cancelled_write_bytes,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_io_sampler_rates() {
+        let identity = ProcessIdentity {
+            pid: 1,
+            start_ticks: 10,
+        };
+        let counters = |bytes, calls| IoCounters {
+            read_bytes: bytes,
+            write_bytes: bytes * 2,
+            read_calls: calls,
+            write_calls: calls,
+            cancelled_write_bytes: 0,
+        };
+        let mut sampler = IoSampler::new(100.0);
+        let start = Instant::now();
+
+        sampler.sampler.begin_tick_at(start, None);
+        assert_eq!(
+            sampler.sample(identity, counters(1_000, 10)),
+            IoRates::default()
+        );
+        sampler.finish_tick();
+
+        sampler
+            .sampler
+            .begin_tick_at(start + Duration::from_secs(2), None);
+        let rates = sampler.sample(identity, counters(5_000, 30));
+        assert_eq!(rates.read_bytes, 2_000);
+        assert_eq!(rates.write_bytes, 4_000);
+        assert_eq!(rates.read_calls, 10);
+        sampler.finish_tick();
+
+        // too soon, the previous rates are kept
+        sampler
+            .sampler
+            .begin_tick_at(start + Duration::from_millis(2100), None);
+        assert_eq!(sampler.sample(identity, counters(6_000, 31)), rates);
+        sampler.finish_tick();
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use procfs::process::{MMPermissions, MMapPath, MemoryMap, Process};
+use slint::StandardListViewItem;
+
+use crate::{
+    manager::{process_handle::ActionError, process_identity::ProcessIdentity},
+    utils::human_readable_byte::human_readable_byte,
+};
+
+/// An entry of `/proc/<pid>/smaps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryMapping {
+    pub start: u64,
+    pub end: u64,
+    /// As `maps` writes them, e.g. `r-xp`.
+    pub permissions: String,
+    pub offset: u64,
+    /// The file backing the mapping, or what the kernel calls it otherwise, e.g. `[heap]`.
+    pub backing: String,
+    pub rss: u64,
+    pub pss: u64,
+    pub swap: u64,
+}
+
+impl From<MemoryMap> for MemoryMapping {
+    fn from(map: MemoryMap) -> Self {
+        let stat = |key: &str| map.extension.map.get(key).copied().unwrap_or_default();
+        let permissions = [
+            (MMPermissions::READ, 'r'),
+            (MMPermissions::WRITE, 'w'),
+            (MMPermissions::EXECUTE, 'x'),
+        ]
+        .into_iter()
+        .map(|(permission, flag)| {
+            if map.perms.contains(permission) {
+                flag
+            } else {
+                '-'
+            }
+        })
+        .chain([if map.perms.contains(MMPermissions::SHARED) {
+            's'
+        } else {
+            'p'
+        }])
+        .collect();
+
+        Self {
+            start: map.address.0,
+            end: map.address.1,
+            permissions,
+            offset: map.offset,
+            rss: stat("Rss"),
+            pss: stat("Pss"),
+            swap: stat("Swap"),
+            backing: match map.pathname {
+                MMapPath::Path(path) => path.display().to_string(),
+                MMapPath::Heap => "[heap]".to_string(),
+                MMapPath::Stack => "[stack]".to_string(),
+                MMapPath::TStack(tid) => format!("[stack:{tid}]"),
+                MMapPath::Vdso => "[vdso]".to_string(),
+                MMapPath::Vvar => "[vvar]".to_string(),
+                MMapPath::Vsyscall => "[vsyscall]".to_string(),
+                MMapPath::Rollup => "[rollup]".to_string(),
+                MMapPath::Anonymous => "[anon]".to_string(),
+                MMapPath::Vsys(key) => format!("[sysv shm {key}]"),
+                MMapPath::Other(name) => name,
+            },
+        }
+    }
+}
+
+impl MemoryMapping {
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// The cells of the row of the mapping in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<StandardListViewItem> {
+        [
+            format!("{:x}-{:x}", self.start, self.end),
+            self.permissions.clone(),
+            format!("{:x}", self.offset),
+            self.backing.clone(),
+            human_readable_byte(self.size()),
+            human_readable_byte(self.rss),
+            human_readable_byte(self.pss),
+            human_readable_byte(self.swap),
+        ]
+        .into_iter()
+        .map(|cell| StandardListViewItem::from(cell.as_str()))
+        .collect()
+    }
+}
+
+/// The mappings of the same file added up, a library is mapped once per segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingGroup {
+    pub backing: String,
+    pub mappings: usize,
+    pub size: u64,
+    pub rss: u64,
+    pub pss: u64,
+    pub swap: u64,
+}
+
+impl MappingGroup {
+    /// The cells of the row of the group in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<StandardListViewItem> {
+        [
+            self.backing.clone(),
+            self.mappings.to_string(),
+            human_readable_byte(self.size),
+            human_readable_byte(self.rss),
+            human_readable_byte(self.pss),
+            human_readable_byte(self.swap),
+        ]
+        .into_iter()
+        .map(|cell| StandardListViewItem::from(cell.as_str()))
+        .collect()
+    }
+}
+
+/// The mappings of the process in address order.
+pub fn memory_maps_of(identity: ProcessIdentity) -> Result<Vec<MemoryMapping>, ActionError> {
+    identity.verify()?;
+    let maps = Process::new(identity.pid)?.smaps()?;
+    Ok(maps.into_iter().map(MemoryMapping::from).collect())
+}
+
+/// Groups the mappings by what backs them, the largest resident groups first. The
+/// anonymous mappings end up in a single `[anon]` group.
+pub fn group_by_backing(mappings: &[MemoryMapping]) -> Vec<MappingGroup> {
+    let mut groups = HashMap::<&str, MappingGroup>::new();
+    for mapping in mappings {
+        let group = groups
+            .entry(&mapping.backing)
+            .or_insert_with(|| MappingGroup {
+                backing: mapping.backing.clone(),
+                mappings: 0,
+                size: 0,
+                rss: 0,
+                pss: 0,
+                swap: 0,
+            });
+        group.mappings += 1;
+        group.size += mapping.size();
+        group.rss += mapping.rss;
+        group.pss += mapping.pss;
+        group.swap += mapping.swap;
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.rss.cmp(&a.rss).then_with(|| a.backing.cmp(&b.backing)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_backing() {
+        let mapping = |start: u64, backing: &str, rss| MemoryMapping {
+            start,
+            end: start + 0x1000,
+            permissions: "r--p".to_string(),
+            offset: 0,
+            backing: backing.to_string(),
+            rss,
+            pss: rss / 2,
+            swap: 0,
+        };
+        let mappings = [
+            mapping(0x1000, "/usr/lib/libxul.so", 4096),
+            mapping(0x2000, "/usr/lib/libxul.so", 8192),
+            mapping(0x3000, "[heap]", 4096),
+            mapping(0x4000, "/usr/lib/libc.so.6", 0),
+        ];
+
+        let groups = group_by_backing(&mappings);
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group.backing.as_str())
+                .collect::<Vec<_>>(),
+            ["/usr/lib/libxul.so", "[heap]", "/usr/lib/libc.so.6"]
+        );
+        assert_eq!(groups[0].mappings, 2);
+        assert_eq!(groups[0].size, 0x2000);
+        assert_eq!(groups[0].rss, 12288);
+        assert_eq!(groups[0].pss, 6144);
+    }
+
+    #[test]
+    fn test_memory_maps_of_myself() {
+        let myself = Process::myself().unwrap();
+        let identity = ProcessIdentity {
+            pid: myself.pid(),
+            start_ticks: myself.stat().unwrap().starttime,
+        };
+
+        let mappings = memory_maps_of(identity).unwrap();
+        assert!(mappings.iter().any(|mapping| mapping.backing == "[stack]"));
+        assert!(mappings.iter().any(|mapping| mapping.rss > 0));
+        assert!(mappings.windows(2).all(|pair| pair[0].end <= pair[1].start));
+    }
+}
@@ -0,0 +1,68 @@
+use std::{fmt, str::FromStr};
+
+/// Memory of a process in bytes. The values only readable by the owner of the process
+/// (or root) are `None` for the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// Resident set size, pages shared with other processes are counted in full.
+    pub rss: u64,
+    /// Proportional set size, shared pages are split between the processes sharing them.
+    pub pss: Option<u64>,
+    /// Unique set size, what would be freed if the process exited.
+    pub uss: Option<u64>,
+    /// Resident file-backed and shared memory.
+    pub shared: u64,
+    pub swap: Option<u64>,
+}
+
+/// Which value of [`MemoryUsage`] the Memory column shows. RSS by default, as the
+/// other users' processes only have that one readable.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryMetric {
+    #[default]
+    RSS,
+    PSS,
+    USS,
+    Shared,
+    Swap,
+}
+
+impl MemoryUsage {
+    pub fn get(&self, metric: MemoryMetric) -> Option<u64> {
+        match metric {
+            MemoryMetric::RSS => Some(self.rss),
+            MemoryMetric::PSS => self.pss,
+            MemoryMetric::USS => self.uss,
+            MemoryMetric::Shared => Some(self.shared),
+            MemoryMetric::Swap => self.swap,
+        }
+    }
+}
+
+impl FromStr for MemoryMetric {
+    type Err = String;
+
+    fn from_str(metric: &str) -> Result<Self, Self::Err> {
+        match metric.to_lowercase().as_str() {
+            "rss" => Ok(Self::RSS),
+            "pss" => Ok(Self::PSS),
+            "uss" => Ok(Self::USS),
+            "shared" => Ok(Self::Shared),
+            "swap" => Ok(Self::Swap),
+            _ => Err(format!("unknown memory metric: {metric}")),
+        }
+    }
+}
+
+impl fmt::Display for MemoryMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RSS => write!(f, "RSS"),
+            Self::PSS => write!(f, "PSS"),
+            Self::USS => write!(f, "USS"),
+            Self::Shared => write!(f, "Shared"),
+            Self::Swap => write!(f, "Swap"),
+        }
+    }
+}
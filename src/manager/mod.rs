@@ -1,29 +1,113 @@
-use std::path::PathBuf;
-
 use chrono::{DateTime, Local};
 use procfs::{ProcResult, process::ProcState};
 
+pub mod cpu_sampler;
+pub mod credentials;
+pub mod deleted_files;
+pub mod environment;
+pub mod fd_table;
 pub mod get_sorted_process_list;
+pub mod handles;
+pub mod interval_sampler;
+pub mod io_sampler;
+pub mod memory_maps;
+pub mod memory_usage;
+pub mod open_files_cache;
+pub mod outdated_files;
+pub mod outdated_files_cache;
+pub mod process_fate;
+pub mod process_table_model;
+pub mod scan_state;
+pub mod security_label;
+pub mod sockets;
 mod traits;
-pub use traits::to_standard_list_view_items::ToStandardListViewItems;
+pub use task_manager_rs_helper::{
+    MyProcessID, process_handle, process_identity, process_tree, scheduling,
+};
+pub use traits::{
+    get_procs_using_file::{FileUse, FileUseReason, GetProcsUsingFile},
+    to_standard_list_view_items::ToStandardListViewItems,
+};
 
-include!(concat!(env!("OUT_DIR"), "/column_enum.rs"));
+use crate::manager::{
+    credentials::Credentials,
+    io_sampler::{IoCounters, IoRates},
+    memory_usage::MemoryUsage,
+    outdated_files::OutdatedFile,
+    process_identity::ProcessIdentity,
+    scheduling::{IoPriority, SchedPolicy},
+    security_label::SecurityLabel,
+};
 
-type MyProcessID = i32;
+include!(concat!(env!("OUT_DIR"), "/column_enum.rs"));
 
 #[derive(Debug, Clone)]
 pub struct MyProcess {
     pub name: String,
     pub id: MyProcessID,
+    /// Start time of the process in clock ticks since boot, see [`MyProcess::identity`].
+    pub start_ticks: u64,
     pub parent_id: MyProcessID,
+    /// Set on the rows of the threads of an expanded process.
+    pub thread_of: Option<MyProcessID>,
+    pub threads: i64,
+    /// The kernel priority, `20 + nice` for normal processes and `-1 - rt_priority`
+    /// for realtime ones.
+    pub priority: i64,
+    pub nice: i64,
+    /// `None` on kernels older than 2.5.19.
+    pub sched_policy: Option<SchedPolicy>,
+    /// `None` when the process exited before it was read.
+    pub io_priority: Option<IoPriority>,
+    /// User + system time in clock ticks, [`MyProcess::cpu_percent`] is sampled from it.
+    pub cpu_ticks: u64,
     pub cpu_percent: f32,
-    pub memory_bytes: u64,
+    pub cpu_total_percent: f32,
+    /// The core the process (or thread) last ran on.
+    pub last_cpu: Option<i32>,
+    /// The CPUs the process (or thread) may run on as inclusive ranges, `None` when
+    /// `/proc/<pid>/status` cannot be read.
+    pub allowed_cpus: Option<Vec<(u32, u32)>>,
+    pub memory: MemoryUsage,
+    /// `None` when `/proc/<pid>/io` is not readable, i.e. for processes of other users.
+    pub io: Option<IoCounters>,
+    pub io_rates: Option<IoRates>,
+    /// `None` when the maps of the process cannot be read, i.e. for processes of other
+    /// users.
+    pub outdated_files: Option<Vec<OutdatedFile>>,
+    /// How the process uses the path of an `@file` search, empty without one.
+    pub file_uses: Vec<FileUse>,
+    /// `None` for threads, and when `/proc/<pid>/status` cannot be read.
+    pub credentials: Option<Credentials>,
+    pub security_label: SecurityLabel,
     pub state: MyProcState,
     pub start_time: Option<DateTime<Local>>,
     pub user: String,
     pub command: String,
+}
+
+/// Tells the rows of the process list apart, the main thread of a process has the same
+/// ID and start time as the process itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RowIdentity {
+    pub identity: ProcessIdentity,
+    pub thread_of: Option<MyProcessID>,
+}
 
-    pub files_using: Vec<PathBuf>,
+impl MyProcess {
+    pub fn identity(&self) -> ProcessIdentity {
+        ProcessIdentity {
+            pid: self.id,
+            start_ticks: self.start_ticks,
+        }
+    }
+
+    pub fn row_identity(&self) -> RowIdentity {
+        RowIdentity {
+            identity: self.identity(),
+            thread_of: self.thread_of,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -0,0 +1,79 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use procfs::process::Process;
+
+use crate::manager::{process_identity::ProcessIdentity, traits::get_using_files::GetUsingFiles};
+
+/// How long the open files of a process are reused before they are read again.
+const MAX_AGE: Duration = Duration::from_secs(10);
+
+/// The files opened by each process, read only when they are asked for. Walking every
+/// fd of every process is the most expensive part of a scan, so it is left out of the
+/// regular refresh.
+#[derive(Debug, Default)]
+pub struct OpenFilesCache {
+    entries: HashMap<ProcessIdentity, (Instant, Arc<[PathBuf]>)>,
+}
+
+impl OpenFilesCache {
+    pub fn files_of(&mut self, identity: ProcessIdentity) -> Arc<[PathBuf]> {
+        self.files_of_at(identity, Instant::now())
+    }
+
+    fn files_of_at(&mut self, identity: ProcessIdentity, now: Instant) -> Arc<[PathBuf]> {
+        if let Some((read_at, files)) = self.entries.get(&identity)
+            && now.duration_since(*read_at) < MAX_AGE
+        {
+            return files.clone();
+        }
+
+        // the PID may have been given to another process since the scan
+        let files: Arc<[PathBuf]> = match identity.verify() {
+            Ok(()) => Process::new(identity.pid)
+                .map(|process| process.using_files().into())
+                .unwrap_or_default(),
+            Err(_) => Arc::default(),
+        };
+        self.entries.insert(identity, (now, files.clone()));
+        files
+    }
+
+    /// Forgets the processes that are gone.
+    pub fn retain(&mut self, alive: &HashSet<ProcessIdentity>) {
+        self.entries.retain(|identity, _| alive.contains(identity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_files_cache_max_age() {
+        let myself = Process::myself().unwrap();
+        let identity = ProcessIdentity {
+            pid: myself.pid(),
+            start_ticks: myself.stat().unwrap().starttime,
+        };
+        let mut cache = OpenFilesCache::default();
+        let start = Instant::now();
+
+        let files = cache.files_of_at(identity, start);
+        assert!(Arc::ptr_eq(
+            &files,
+            &cache.files_of_at(identity, start + Duration::from_secs(1))
+        ));
+        assert!(!Arc::ptr_eq(
+            &files,
+            &cache.files_of_at(identity, start + MAX_AGE)
+        ));
+
+        cache.retain(&HashSet::new());
+        assert!(cache.entries.is_empty());
+    }
+}
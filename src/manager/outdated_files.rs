@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+use slint::StandardListViewItem;
+
+use crate::manager::{MyProcess, process_identity::ProcessIdentity};
+
+/// How the file a process was started from, or mapped, differs from what is on disk now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Staleness {
+    /// Nothing is left at the path, e.g. a package was removed.
+    Deleted,
+    /// Another file took its place, which is how package managers upgrade.
+    Replaced,
+}
+
+impl fmt::Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deleted => write!(f, "deleted"),
+            Self::Replaced => write!(f, "replaced"),
+        }
+    }
+}
+
+/// An executable or library a process still runs from while it is gone from disk.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutdatedFile {
+    pub path: PathBuf,
+    pub staleness: Staleness,
+}
+
+/// The processes still running an outdated file, these need a restart to pick up the
+/// new version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedGroup {
+    pub file: OutdatedFile,
+    pub processes: Vec<(String, ProcessIdentity)>,
+}
+
+impl OutdatedGroup {
+    /// The cells of the row of the group in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<StandardListViewItem> {
+        let processes = self
+            .processes
+            .iter()
+            .map(|(name, identity)| format!("{name} ({})", identity.pid))
+            .collect::<Vec<_>>()
+            .join(", ");
+        [
+            self.file.path.display().to_string(),
+            self.file.staleness.to_string(),
+            self.processes.len().to_string(),
+            processes,
+        ]
+        .into_iter()
+        .map(|cell| StandardListViewItem::from(cell.as_str()))
+        .collect()
+    }
+}
+
+/// Groups the processes by the outdated file they use, the files used by the most
+/// processes first.
+pub fn group_by_file<'a>(processes: impl IntoIterator<Item = &'a MyProcess>) -> Vec<OutdatedGroup> {
+    let mut groups = BTreeMap::<&OutdatedFile, Vec<(String, ProcessIdentity)>>::new();
+    for process in processes {
+        for file in process.outdated_files.iter().flatten() {
+            groups
+                .entry(file)
+                .or_default()
+                .push((process.name.clone(), process.identity()));
+        }
+    }
+
+    let mut groups = groups
+        .into_iter()
+        .map(|(file, processes)| OutdatedGroup {
+            file: file.clone(),
+            processes,
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.processes.len()));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::{MyProcState, memory_usage::MemoryUsage, security_label::SecurityLabel};
+
+    #[test]
+    fn test_group_by_file() {
+        let libssl = OutdatedFile {
+            path: PathBuf::from("/usr/lib/libssl.so.3"),
+            staleness: Staleness::Replaced,
+        };
+        let daemon = OutdatedFile {
+            path: PathBuf::from("/usr/sbin/daemon"),
+            staleness: Staleness::Deleted,
+        };
+        let process = |id, outdated_files: Option<Vec<OutdatedFile>>| MyProcess {
+            name: format!("process-{id}"),
+            id,
+            start_ticks: 100,
+            parent_id: 1,
+            thread_of: None,
+            threads: 1,
+            priority: 20,
+            nice: 0,
+            sched_policy: None,
+            io_priority: None,
+            cpu_ticks: 0,
+            cpu_percent: 0.0,
+            cpu_total_percent: 0.0,
+            last_cpu: None,
+            allowed_cpus: None,
+            memory: MemoryUsage::default(),
+            io: None,
+            io_rates: None,
+            outdated_files,
+            file_uses: vec![],
+            credentials: None,
+            security_label: SecurityLabel::None,
+            state: MyProcState::Sleeping,
+            start_time: None,
+            user: String::new(),
+            command: String::new(),
+        };
+        let processes = [
+            process(10, Some(vec![libssl.clone(), daemon.clone()])),
+            process(11, Some(vec![libssl.clone()])),
+            process(12, Some(vec![])),
+            process(13, None),
+        ];
+
+        let groups = group_by_file(&processes);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].file, libssl);
+        assert_eq!(
+            groups[0]
+                .processes
+                .iter()
+                .map(|(_, identity)| identity.pid)
+                .collect::<Vec<_>>(),
+            [10, 11]
+        );
+        assert_eq!(groups[1].file, daemon);
+        assert_eq!(groups[1].processes.len(), 1);
+    }
+}
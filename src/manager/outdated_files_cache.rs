@@ -0,0 +1,117 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::manager::{outdated_files::OutdatedFile, process_identity::ProcessIdentity};
+
+/// How long the outdated files of a process are reused before they are checked again.
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+/// The outdated files of each process. Checking them stats the executable and every
+/// mapped library of every process while upgrades are rare, so a process is checked
+/// when it shows up, then only once a minute, when the user asks for it, or when a
+/// library directory changed.
+#[derive(Debug, Default)]
+pub struct OutdatedFilesCache {
+    entries: HashMap<ProcessIdentity, (Instant, Option<Vec<OutdatedFile>>)>,
+    /// The modification times of the library directories when they were last looked at.
+    library_dirs: Vec<Option<SystemTime>>,
+}
+
+impl OutdatedFilesCache {
+    /// Returns the cached files of the process, or those found by `check` when there are
+    /// none yet or they are too old.
+    pub fn files_of(
+        &mut self,
+        identity: ProcessIdentity,
+        now: Instant,
+        check: impl FnOnce() -> Option<Vec<OutdatedFile>>,
+    ) -> Option<Vec<OutdatedFile>> {
+        if let Some((checked_at, files)) = self.entries.get(&identity)
+            && now.duration_since(*checked_at) < MAX_AGE
+        {
+            return files.clone();
+        }
+
+        let files = check();
+        self.entries.insert(identity, (now, files.clone()));
+        files
+    }
+
+    /// Makes the next scan check every process again, e.g. right after an upgrade.
+    pub fn invalidate(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Checks every process again when the modification times of the library directories
+    /// differ from the last ones, an upgrade renames the new files into them.
+    pub fn update_library_dirs(&mut self, modified: Vec<Option<SystemTime>>) {
+        if self.library_dirs != modified {
+            if !self.library_dirs.is_empty() {
+                self.invalidate();
+            }
+            self.library_dirs = modified;
+        }
+    }
+
+    /// Forgets the processes that are gone.
+    pub fn retain(&mut self, alive: &HashSet<ProcessIdentity>) {
+        self.entries.retain(|identity, _| alive.contains(identity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::manager::outdated_files::Staleness;
+
+    #[test]
+    fn test_outdated_files_cache_max_age() {
+        let identity = ProcessIdentity {
+            pid: 42,
+            start_ticks: 7,
+        };
+        let replaced = vec![OutdatedFile {
+            path: PathBuf::from("/usr/lib/libc.so.6"),
+            staleness: Staleness::Replaced,
+        }];
+        let mut cache = OutdatedFilesCache::default();
+        let start = Instant::now();
+
+        assert_eq!(
+            cache.files_of(identity, start, || Some(vec![])),
+            Some(vec![])
+        );
+        // the upgrade goes unnoticed until the entry is too old or invalidated
+        let check = || Some(replaced.clone());
+        assert_eq!(
+            cache.files_of(identity, start + Duration::from_secs(1), check),
+            Some(vec![])
+        );
+        assert_eq!(
+            cache.files_of(identity, start + MAX_AGE, check),
+            Some(replaced.clone())
+        );
+
+        cache.invalidate();
+        assert_eq!(cache.files_of(identity, start + MAX_AGE, || None), None);
+
+        // the first look only remembers the library directories, an upgrade changes one
+        cache.update_library_dirs(vec![Some(SystemTime::UNIX_EPOCH)]);
+        assert_eq!(
+            cache.files_of(identity, start + MAX_AGE, || Some(vec![])),
+            None
+        );
+        cache.update_library_dirs(vec![Some(SystemTime::now())]);
+        assert_eq!(
+            cache.files_of(identity, start + MAX_AGE, || Some(vec![])),
+            Some(vec![])
+        );
+
+        cache.retain(&HashSet::new());
+        assert!(cache.entries.is_empty());
+    }
+}
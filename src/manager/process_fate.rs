@@ -0,0 +1,111 @@
+use std::{fmt, time::Duration};
+
+use procfs::process::Process;
+use tokio::time::Instant;
+
+use crate::manager::{MyProcessID, process_identity::ProcessIdentity};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What became of a process after it was asked to terminate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessFate {
+    Exited,
+    /// Terminated, but its parent has not collected the exit status yet.
+    Zombie {
+        parent_id: MyProcessID,
+    },
+    /// Blocked in the kernel (D state), signals are only acted upon once it wakes up.
+    UninterruptibleSleep,
+    Alive,
+}
+
+impl ProcessFate {
+    pub fn of(identity: ProcessIdentity) -> Self {
+        let Ok(stat) = Process::new(identity.pid).and_then(|process| process.stat()) else {
+            return Self::Exited;
+        };
+        if stat.starttime != identity.start_ticks {
+            return Self::Exited;
+        }
+        match stat.state {
+            'Z' => Self::Zombie {
+                parent_id: stat.ppid,
+            },
+            'X' | 'x' => Self::Exited,
+            'D' => Self::UninterruptibleSleep,
+            _ => Self::Alive,
+        }
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        matches!(self, Self::Exited | Self::Zombie { .. })
+    }
+
+    /// Polls the process until it terminates or the grace period runs out.
+    pub async fn wait_for_exit(identity: ProcessIdentity, grace_period: Duration) -> Self {
+        let deadline = Instant::now() + grace_period;
+        loop {
+            let fate = Self::of(identity);
+            if fate.is_terminated() || Instant::now() >= deadline {
+                return fate;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl fmt::Display for ProcessFate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exited => write!(f, "exited"),
+            Self::Zombie { parent_id } => write!(
+                f,
+                "terminated, but is a zombie until its parent ({parent_id}) reaps it"
+            ),
+            Self::UninterruptibleSleep => write!(f, "is stuck in uninterruptible sleep (D state)"),
+            Self::Alive => write!(f, "is still running"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_for_exit() {
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg("trap '' TERM; exec sleep 10")
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(Duration::from_millis(200)); // Give time for the trap to be set
+
+        let pid = child.id() as MyProcessID;
+        let identity = ProcessIdentity {
+            pid,
+            start_ticks: Process::new(pid).unwrap().stat().unwrap().starttime,
+        };
+
+        // SIGTERM is ignored, so the grace period runs out
+        unsafe { libc::kill(pid, libc::SIGTERM) };
+        assert_eq!(
+            ProcessFate::wait_for_exit(identity, Duration::from_millis(300)).await,
+            ProcessFate::Alive
+        );
+
+        // not reaped yet, so it lingers as our zombie
+        unsafe { libc::kill(pid, libc::SIGKILL) };
+        assert_eq!(
+            ProcessFate::wait_for_exit(identity, Duration::from_secs(5)).await,
+            ProcessFate::Zombie {
+                parent_id: std::process::id() as MyProcessID
+            }
+        );
+
+        let _ = child.wait();
+        assert_eq!(ProcessFate::of(identity), ProcessFate::Exited);
+    }
+}
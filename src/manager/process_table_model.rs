@@ -0,0 +1,177 @@
+use std::{any::Any, cell::RefCell, collections::HashSet, rc::Rc};
+
+use slint::{Model, ModelNotify, ModelRc, ModelTracker, StandardListViewItem, VecModel};
+
+use crate::manager::RowIdentity;
+
+/// A row-level change turning the previous snapshot into the next one, indices refer to
+/// the rows as they are when the change is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowChange {
+    Remove(usize),
+    /// Inserts the row at this index of the new snapshot.
+    Insert(usize),
+    /// The row already shows this process, only its cells may have changed.
+    Update(usize),
+}
+
+/// Compares two snapshots by row identity. Rows that moved are removed and inserted
+/// again, the others keep their place so the view does not jump around.
+fn diff_rows(old: &[RowIdentity], new: &[RowIdentity]) -> Vec<RowChange> {
+    let still_listed = new.iter().collect::<HashSet<_>>();
+    let mut current = old.to_vec();
+    let mut changes = vec![];
+
+    for row in (0..current.len()).rev() {
+        if !still_listed.contains(&current[row]) {
+            current.remove(row);
+            changes.push(RowChange::Remove(row));
+        }
+    }
+
+    for (row, identity) in new.iter().enumerate() {
+        if current.get(row) == Some(identity) {
+            changes.push(RowChange::Update(row));
+            continue;
+        }
+        if let Some(moved_from) = current[row..].iter().position(|c| c == identity) {
+            current.remove(row + moved_from);
+            changes.push(RowChange::Remove(row + moved_from));
+        }
+        current.insert(row, *identity);
+        changes.push(RowChange::Insert(row));
+    }
+
+    changes
+}
+
+/// The process table, kept across refreshes and updated row by row so the scroll
+/// position and the selection survive a refresh.
+#[derive(Default)]
+pub struct ProcessTableModel {
+    rows: RefCell<Vec<(RowIdentity, Rc<VecModel<StandardListViewItem>>)>>,
+    notify: ModelNotify,
+}
+
+impl ProcessTableModel {
+    /// Brings the table in line with a new snapshot, only cells whose text changed are
+    /// touched.
+    pub fn update(&self, snapshot: Vec<(RowIdentity, Vec<StandardListViewItem>)>) {
+        let old = self
+            .rows
+            .borrow()
+            .iter()
+            .map(|(identity, _)| *identity)
+            .collect::<Vec<_>>();
+        let (new, mut cells): (Vec<_>, Vec<_>) = snapshot
+            .into_iter()
+            .map(|(identity, cells)| (identity, Some(cells)))
+            .unzip();
+
+        for change in diff_rows(&old, &new) {
+            match change {
+                RowChange::Remove(row) => {
+                    self.rows.borrow_mut().remove(row);
+                    self.notify.row_removed(row, 1);
+                }
+                RowChange::Insert(row) => {
+                    let row_cells = VecModel::from(cells[row].take().unwrap_or_default());
+                    self.rows
+                        .borrow_mut()
+                        .insert(row, (new[row], Rc::new(row_cells)));
+                    self.notify.row_added(row, 1);
+                }
+                RowChange::Update(row) => {
+                    let row_cells = self.rows.borrow()[row].1.clone();
+                    for (column, cell) in cells[row]
+                        .take()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .enumerate()
+                    {
+                        if row_cells.row_data(column).as_ref() != Some(&cell) {
+                            row_cells.set_row_data(column, cell);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Model for ProcessTableModel {
+    type Data = ModelRc<StandardListViewItem>;
+
+    fn row_count(&self) -> usize {
+        self.rows.borrow().len()
+    }
+
+    fn row_data(&self, row: usize) -> Option<Self::Data> {
+        self.rows
+            .borrow()
+            .get(row)
+            .map(|(_, row_cells)| ModelRc::from(row_cells.clone()))
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        &self.notify
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::process_identity::ProcessIdentity;
+
+    #[test]
+    fn test_diff_rows() {
+        let identity = |pid| RowIdentity {
+            identity: ProcessIdentity {
+                pid,
+                start_ticks: 100,
+            },
+            thread_of: None,
+        };
+        let old = [1, 2, 3, 4, 5].map(identity);
+        let new = [1, 3, 6, 5, 4].map(identity);
+
+        let mut current = old.to_vec();
+        for change in diff_rows(&old, &new) {
+            match change {
+                RowChange::Remove(row) => {
+                    current.remove(row);
+                }
+                RowChange::Insert(row) => current.insert(row, new[row]),
+                RowChange::Update(row) => assert_eq!(current[row], new[row]),
+            }
+        }
+        assert_eq!(current, new);
+
+        // a PID taken over by another process is a different row
+        let reused = [RowIdentity {
+            identity: ProcessIdentity {
+                pid: 1,
+                start_ticks: 200,
+            },
+            thread_of: None,
+        }];
+        assert_eq!(
+            diff_rows(&old[..1], &reused),
+            [RowChange::Remove(0), RowChange::Insert(0)]
+        );
+
+        // so is the main thread of an expanded process
+        let main_thread = RowIdentity {
+            thread_of: Some(1),
+            ..old[0]
+        };
+        assert_eq!(
+            diff_rows(&old[..1], &[old[0], main_thread]),
+            [RowChange::Update(0), RowChange::Insert(1)]
+        );
+    }
+}
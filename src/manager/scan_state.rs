@@ -0,0 +1,20 @@
+use crate::manager::{
+    cpu_sampler::CpuSampler, io_sampler::IoSampler, open_files_cache::OpenFilesCache,
+    outdated_files_cache::OutdatedFilesCache, sockets::SocketOwnersCache,
+};
+
+/// Everything remembered from one scan to the next.
+#[derive(Debug, Default)]
+pub struct ScanState {
+    pub cpu: CpuSampler,
+    /// Threads have their own sampler, the main thread shares its ID and start time
+    /// with its process.
+    pub thread_cpu: CpuSampler,
+    pub io: IoSampler,
+    pub open_files: OpenFilesCache,
+    pub outdated_files: OutdatedFilesCache,
+    pub socket_owners: SocketOwnersCache,
+    /// The processes an `@env` search of the last scan skipped because their environment
+    /// could not be read.
+    pub unreadable_environments: usize,
+}
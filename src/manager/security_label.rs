@@ -0,0 +1,61 @@
+use std::fmt;
+
+/// The SELinux context or AppArmor profile a process runs under.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SecurityLabel {
+    /// An SELinux context such as `system_u:system_r:sshd_t:s0`, or an AppArmor profile
+    /// with its mode such as `/usr/sbin/cupsd (enforce)`.
+    Label(String),
+    /// AppArmor is active but no profile applies to the process.
+    Unconfined,
+    /// No security module that labels processes is active.
+    None,
+    /// The label exists but could not be read.
+    Unreadable,
+}
+
+impl SecurityLabel {
+    /// Parses the content of `/proc/<pid>/attr/current`, which ends with a newline for
+    /// AppArmor and a NUL byte for SELinux.
+    pub fn parse(current: &[u8]) -> Self {
+        let label = String::from_utf8_lossy(current);
+        match label.trim_end_matches(['\0', '\n']).trim() {
+            "" => Self::None,
+            "unconfined" => Self::Unconfined,
+            label => Self::Label(label.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for SecurityLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Label(label) => write!(f, "{label}"),
+            Self::Unconfined => write!(f, "unconfined"),
+            Self::None => write!(f, "none"),
+            Self::Unreadable => write!(f, "N/A"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_security_label() {
+        assert_eq!(
+            SecurityLabel::parse(b"system_u:system_r:sshd_t:s0-s0:c0.c1023\0"),
+            SecurityLabel::Label("system_u:system_r:sshd_t:s0-s0:c0.c1023".to_string())
+        );
+        assert_eq!(
+            SecurityLabel::parse(b"/usr/sbin/cupsd (enforce)\n"),
+            SecurityLabel::Label("/usr/sbin/cupsd (enforce)".to_string())
+        );
+        assert_eq!(
+            SecurityLabel::parse(b"unconfined\n"),
+            SecurityLabel::Unconfined
+        );
+        assert_eq!(SecurityLabel::parse(b""), SecurityLabel::None);
+    }
+}
@@ -0,0 +1,434 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    fmt, fs,
+    net::SocketAddr,
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+    sync::{Arc, atomic::AtomicBool},
+    time::{Duration, Instant},
+};
+
+use procfs::{
+    ProcResult,
+    process::{FDTarget, Process},
+};
+use slint::StandardListViewItem;
+
+use crate::manager::{MyProcessID, fd_table::FdTable};
+
+/// What a socket inode stands for, as listed in the `/proc/<pid>/net` tables of the
+/// network namespace of a process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketInfo {
+    Inet {
+        protocol: &'static str,
+        local: SocketAddr,
+        remote: SocketAddr,
+        state: String,
+    },
+    Unix {
+        path: Option<PathBuf>,
+        state: String,
+    },
+}
+
+impl SocketInfo {
+    pub fn protocol(&self) -> &'static str {
+        match self {
+            Self::Inet { protocol, .. } => protocol,
+            Self::Unix { .. } => "unix",
+        }
+    }
+
+    pub fn local_address(&self) -> String {
+        match self {
+            Self::Inet { local, .. } => local.to_string(),
+            Self::Unix { path, .. } => path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Empty for listening and unconnected sockets.
+    pub fn remote_address(&self) -> String {
+        match self {
+            Self::Inet { remote, .. } if has_address(remote) => remote.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn state(&self) -> &str {
+        match self {
+            Self::Inet { state, .. } | Self::Unix { state, .. } => state,
+        }
+    }
+
+    /// Whether the socket is bound to the port, as a server listening on it is.
+    pub fn uses_local_port(&self, port: u16) -> bool {
+        match self {
+            Self::Inet { local, .. } => local.port() == port,
+            Self::Unix { .. } => false,
+        }
+    }
+
+    /// Whether the socket is connected to the port, as a client of a server on it is.
+    pub fn uses_remote_port(&self, port: u16) -> bool {
+        match self {
+            Self::Inet { remote, .. } => has_address(remote) && remote.port() == port,
+            Self::Unix { .. } => false,
+        }
+    }
+}
+
+fn has_address(address: &SocketAddr) -> bool {
+    !address.ip().is_unspecified() || address.port() != 0
+}
+
+impl fmt::Display for SocketInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inet {
+                protocol,
+                local,
+                remote,
+                state,
+            } => {
+                write!(f, "{protocol} {local}")?;
+                // listening and unconnected sockets have no remote end
+                if has_address(remote) {
+                    write!(f, " -> {remote}")?;
+                }
+                write!(f, " ({state})")
+            }
+            Self::Unix { path, state } => match path {
+                Some(path) => write!(f, "unix {} ({state})", path.display()),
+                None => write!(f, "unix ({state})"),
+            },
+        }
+    }
+}
+
+/// The sockets of a network namespace by inode.
+#[derive(Debug, Default)]
+pub struct SocketTable {
+    sockets: HashMap<u64, SocketInfo>,
+}
+
+impl SocketTable {
+    /// Reads the tables of the namespace the process lives in, the ones that cannot be
+    /// read (e.g. no IPv6 support) are left out.
+    pub fn of(process: &Process) -> Self {
+        let mut sockets = HashMap::new();
+
+        for (protocol, entries) in [("tcp", process.tcp()), ("tcp6", process.tcp6())] {
+            for entry in entries.into_iter().flatten() {
+                sockets.insert(
+                    entry.inode,
+                    SocketInfo::Inet {
+                        protocol,
+                        local: entry.local_address,
+                        remote: entry.remote_address,
+                        state: format!("{:?}", entry.state),
+                    },
+                );
+            }
+        }
+        for (protocol, entries) in [("udp", process.udp()), ("udp6", process.udp6())] {
+            for entry in entries.into_iter().flatten() {
+                sockets.insert(
+                    entry.inode,
+                    SocketInfo::Inet {
+                        protocol,
+                        local: entry.local_address,
+                        remote: entry.remote_address,
+                        state: format!("{:?}", entry.state),
+                    },
+                );
+            }
+        }
+        for entry in process.unix().into_iter().flatten() {
+            sockets.insert(
+                entry.inode,
+                SocketInfo::Unix {
+                    path: entry.path,
+                    state: format!("{:?}", entry.state),
+                },
+            );
+        }
+
+        Self { sockets }
+    }
+
+    pub fn get(&self, inode: u64) -> Option<&SocketInfo> {
+        self.sockets.get(&inode)
+    }
+}
+
+/// A process holding a socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketOwner {
+    pub pid: MyProcessID,
+    pub name: String,
+    pub fd: i32,
+}
+
+impl fmt::Display for SocketOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}) fd {}", self.name, self.pid, self.fd)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub socket: SocketInfo,
+    /// Empty when the socket is held by processes whose fds cannot be read, those of
+    /// other users unless running as root, or by no process at all (e.g. `TIME_WAIT`).
+    pub owners: Vec<SocketOwner>,
+}
+
+impl Connection {
+    /// The cells of the row of the connection in the details pane.
+    pub fn to_standard_list_view_items(&self) -> Vec<StandardListViewItem> {
+        let owners = self
+            .owners
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        [
+            self.socket.protocol().to_string(),
+            self.socket.local_address(),
+            self.socket.remote_address(),
+            self.socket.state().to_string(),
+            owners,
+        ]
+        .into_iter()
+        .map(|cell| StandardListViewItem::from(cell.as_str()))
+        .collect()
+    }
+}
+
+/// The processes holding each socket, keyed by network namespace and inode.
+#[derive(Debug, Default)]
+pub struct SocketOwners {
+    owners: HashMap<(u64, u64), Vec<SocketOwner>>,
+    /// A process of each namespace, whose net tables list the sockets of the namespace.
+    namespaces: HashMap<u64, MyProcessID>,
+}
+
+impl SocketOwners {
+    pub fn of(fd_table: &FdTable) -> Self {
+        let mut socket_owners = Self::default();
+        for process in fd_table.processes() {
+            let sockets = process
+                .fds
+                .iter()
+                .filter_map(|fd| match fd.target {
+                    FDTarget::Socket(inode) => Some((fd.fd, inode)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if sockets.is_empty() {
+                continue;
+            }
+            let pid = process.identity.pid;
+            let Ok(namespace) = net_namespace(pid) else {
+                continue;
+            };
+
+            socket_owners.namespaces.entry(namespace).or_insert(pid);
+            for (fd, inode) in sockets {
+                socket_owners
+                    .owners
+                    .entry((namespace, inode))
+                    .or_default()
+                    .push(SocketOwner {
+                        pid,
+                        name: process.name.clone(),
+                        fd,
+                    });
+            }
+        }
+        socket_owners
+    }
+}
+
+/// The socket owners of the `@port` searches, reused by the scans of the next seconds.
+/// The sockets themselves are always read fresh, a matching socket without an owner
+/// was opened since the fds were walked, so they are walked again.
+#[derive(Debug, Default)]
+pub struct SocketOwnersCache {
+    cached: Option<(Instant, Arc<SocketOwners>)>,
+    /// The sockets still without an owner right after a walk, those of the processes
+    /// that cannot be inspected. They do not make the fds be walked again.
+    unowned: HashSet<(u64, u64)>,
+}
+
+impl SocketOwnersCache {
+    const MAX_AGE: Duration = Duration::from_secs(10);
+
+    /// The processes holding a socket bound to the port, or connected to it when
+    /// `remote`. Returns `None` when `cancelled` gets set while the fds are walked.
+    pub fn procs_using_port(
+        &mut self,
+        port: u16,
+        remote: bool,
+        cancelled: &AtomicBool,
+    ) -> ProcResult<Option<Vec<MyProcessID>>> {
+        if let Some((read_at, _)) = &self.cached
+            && read_at.elapsed() >= Self::MAX_AGE
+        {
+            self.cached = None;
+            self.unowned.clear();
+        }
+        loop {
+            let walked = self.cached.is_none();
+            let owners = match &self.cached {
+                Some((_, owners)) => owners.clone(),
+                None => {
+                    let Some(fd_table) = FdTable::read(cancelled)? else {
+                        return Ok(None);
+                    };
+                    let owners = Arc::new(SocketOwners::of(&fd_table));
+                    self.cached = Some((Instant::now(), owners.clone()));
+                    owners
+                }
+            };
+            let connections = keyed_connections_of(&owners)?
+                .into_iter()
+                .filter(|(_, connection)| {
+                    if remote {
+                        connection.socket.uses_remote_port(port)
+                    } else {
+                        connection.socket.uses_local_port(port)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let unowned = connections
+                .iter()
+                .filter(|(_, connection)| connection.owners.is_empty())
+                .map(|(key, _)| *key);
+            if walked {
+                self.unowned.extend(unowned);
+            } else if unowned.into_iter().any(|key| !self.unowned.contains(&key)) {
+                self.cached = None;
+                continue;
+            }
+            return Ok(Some(
+                connections
+                    .into_iter()
+                    .flat_map(|(_, connection)| connection.owners)
+                    .map(|owner| owner.pid)
+                    .collect(),
+            ));
+        }
+    }
+}
+
+/// Every socket of the network namespaces the processes of the table live in, like
+/// `ss -ap` lists them. The sockets are matched to their owners by inode.
+pub fn connections(fd_table: &FdTable) -> ProcResult<Vec<Connection>> {
+    Ok(keyed_connections_of(&SocketOwners::of(fd_table))?
+        .into_iter()
+        .map(|(_, connection)| connection)
+        .collect())
+}
+
+fn keyed_connections_of(socket_owners: &SocketOwners) -> ProcResult<Vec<((u64, u64), Connection)>> {
+    let myself = Process::myself()?;
+    let mut tables = HashMap::from([(net_namespace(myself.pid())?, SocketTable::of(&myself))]);
+    for (&namespace, &pid) in &socket_owners.namespaces {
+        if let Entry::Vacant(entry) = tables.entry(namespace)
+            && let Ok(process) = Process::new(pid)
+        {
+            entry.insert(SocketTable::of(&process));
+        }
+    }
+    let mut owners = socket_owners.owners.clone();
+
+    Ok(tables
+        .into_iter()
+        .flat_map(|(namespace, table)| {
+            table
+                .sockets
+                .into_iter()
+                .map(move |(inode, socket)| ((namespace, inode), socket))
+        })
+        .map(|(key, socket)| {
+            let owners = owners.remove(&key).unwrap_or_default();
+            (key, Connection { socket, owners })
+        })
+        .collect())
+}
+
+/// The inode of the network namespace of the process, processes sharing it see the
+/// same sockets.
+fn net_namespace(pid: MyProcessID) -> ProcResult<u64> {
+    Ok(fs::metadata(format!("/proc/{pid}/ns/net"))?.ino())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_procs_using_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let fd_table = FdTable::read(&AtomicBool::new(false)).unwrap().unwrap();
+        let connection = connections(&fd_table)
+            .unwrap()
+            .into_iter()
+            .find(|connection| connection.socket.uses_local_port(port))
+            .expect("The listening socket should be listed");
+        assert_eq!(connection.socket.protocol(), "tcp");
+        assert_eq!(
+            connection.socket.local_address(),
+            format!("127.0.0.1:{port}")
+        );
+        assert_eq!(connection.socket.remote_address(), "");
+        assert!(
+            connection
+                .owners
+                .iter()
+                .any(|owner| owner.pid == std::process::id() as MyProcessID),
+            "Unexpected owners: {:?}",
+            connection.owners
+        );
+
+        let mut socket_owners = SocketOwnersCache::default();
+        let cancelled = AtomicBool::new(false);
+        assert!(
+            socket_owners
+                .procs_using_port(port, false, &cancelled)
+                .unwrap()
+                .unwrap()
+                .contains(&(std::process::id() as MyProcessID))
+        );
+
+        // a listener opened after the fds were walked is still found
+        let late_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let late_port = late_listener.local_addr().unwrap().port();
+        assert!(
+            socket_owners
+                .procs_using_port(late_port, false, &cancelled)
+                .unwrap()
+                .unwrap()
+                .contains(&(std::process::id() as MyProcessID))
+        );
+
+        // the client end is only connected to the port
+        let client = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let client_address = client.local_addr().unwrap().to_string();
+        let fd_table = FdTable::read(&AtomicBool::new(false)).unwrap().unwrap();
+        let outgoing = connections(&fd_table)
+            .unwrap()
+            .into_iter()
+            .find(|connection| connection.socket.local_address() == client_address)
+            .expect("The client socket should be listed");
+        assert!(outgoing.socket.uses_remote_port(port));
+        assert!(!outgoing.socket.uses_local_port(port));
+    }
+}
@@ -1,31 +1,18 @@
-use procfs::{Current, ProcResult, Uptime, process::Stat, ticks_per_second};
+use crate::manager::{MyProcess, cpu_sampler::CpuSampler};
 
 pub trait CpuPercent {
-    fn cpu_percent(&self) -> ProcResult<f32>;
+    /// Fills in the CPU usage of every process since the previous sampling.
+    fn sample_cpu_percent(&mut self, cpu_sampler: &mut CpuSampler);
 }
 
-impl CpuPercent for Stat {
-    fn cpu_percent(&self) -> ProcResult<f32> {
-        let total_time_ticks = self.utime + self.stime;
-
-        let clk_tck = ticks_per_second() as f32;
-        if clk_tck <= 0.0 {
-            return Ok(0.0);
-        }
-
-        let system_uptime_seconds = Uptime::current()?.uptime as f32;
-
-        let process_starttime_seconds = self.starttime as f32 / clk_tck;
-
-        let process_duration_seconds = system_uptime_seconds - process_starttime_seconds;
-        if process_duration_seconds <= 0.0 {
-            return Ok(0.0);
+impl CpuPercent for Vec<MyProcess> {
+    fn sample_cpu_percent(&mut self, cpu_sampler: &mut CpuSampler) {
+        cpu_sampler.begin_tick();
+        for process in self.iter_mut() {
+            let usage = cpu_sampler.sample(process.identity(), process.cpu_ticks);
+            process.cpu_percent = usage.per_core;
+            process.cpu_total_percent = usage.machine;
         }
-
-        let process_cpu_time_seconds = total_time_ticks as f32 / clk_tck;
-
-        let cpu_usage = (process_cpu_time_seconds / process_duration_seconds) * 10.0;
-
-        Ok(cpu_usage)
+        cpu_sampler.finish_tick();
     }
 }
@@ -0,0 +1,186 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use procfs::{
+    ProcResult,
+    process::{MMPermissions, MMapPath, Process},
+};
+
+use crate::manager::{
+    MyProcess,
+    outdated_files::{OutdatedFile, Staleness},
+    outdated_files_cache::OutdatedFilesCache,
+};
+
+const DELETED_SUFFIX: &str = " (deleted)";
+
+/// Where upgrades put executables and libraries.
+const LIBRARY_DIRS: [&str; 8] = [
+    "/usr/bin",
+    "/usr/sbin",
+    "/usr/lib",
+    "/usr/lib64",
+    "/usr/lib/x86_64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/lib",
+    "/lib64",
+];
+
+/// The inode at a path in a mount namespace, remembered for the scan since every process
+/// maps the same handful of libraries.
+type InodeMemo = HashMap<(u64, PathBuf), Option<u64>>;
+
+pub trait FindOutdatedFiles {
+    /// Flags the processes whose executable or mapped libraries were deleted or replaced
+    /// since they were started, like `needrestart` does. Left `None` when the maps of a
+    /// process cannot be read, i.e. for processes of other users. Only the processes
+    /// missing from `cache`, or too old in it, are checked, all of them once a library
+    /// directory changed.
+    fn find_outdated_files(&mut self, cache: &mut OutdatedFilesCache);
+}
+
+impl FindOutdatedFiles for Vec<MyProcess> {
+    fn find_outdated_files(&mut self, cache: &mut OutdatedFilesCache) {
+        cache.update_library_dirs(
+            LIBRARY_DIRS
+                .iter()
+                .map(|dir| {
+                    fs::metadata(dir)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                })
+                .collect(),
+        );
+        let now = Instant::now();
+        let mut memo = InodeMemo::new();
+        for process in self.iter_mut() {
+            process.outdated_files = cache.files_of(process.identity(), now, || {
+                Process::new(process.id)
+                    .and_then(|proc| outdated_files_of(&proc, &mut memo))
+                    .ok()
+            });
+        }
+        cache.retain(&self.iter().map(MyProcess::identity).collect());
+    }
+}
+
+fn outdated_files_of(process: &Process, memo: &mut InodeMemo) -> ProcResult<Vec<OutdatedFile>> {
+    // paths are resolved from the root of the process, it may live in a container
+    let namespace = fs::metadata(format!("/proc/{}/ns/mnt", process.pid()))?.ino();
+    let root = PathBuf::from(format!("/proc/{}/root", process.pid()));
+    let mut current_inode = |path: &Path| {
+        *memo
+            .entry((namespace, path.to_path_buf()))
+            .or_insert_with(|| {
+                fs::metadata(root.join(path.strip_prefix("/").unwrap_or(path)))
+                    .ok()
+                    .map(|metadata| metadata.ino())
+            })
+    };
+    let mut outdated = BTreeSet::new();
+    let mut check = |path: &Path, inode: u64| {
+        let (path, deleted) = match path.to_str().and_then(|p| p.strip_suffix(DELETED_SUFFIX)) {
+            Some(path) => (PathBuf::from(path), true),
+            None => (path.to_path_buf(), false),
+        };
+        // the inode is compared alone, stat and maps disagree on the device of btrfs
+        // subvolumes and overlay filesystems
+        let staleness = match current_inode(&path) {
+            None if deleted => Staleness::Deleted,
+            Some(current) if current != inode => Staleness::Replaced,
+            // a new file at the path of a deleted one is an upgrade as well
+            Some(_) if deleted => Staleness::Replaced,
+            _ => return,
+        };
+        outdated.insert(OutdatedFile { path, staleness });
+    };
+
+    let exe = process.exe()?;
+    let exe_inode = fs::metadata(format!("/proc/{}/exe", process.pid()))?.ino();
+    check(&exe, exe_inode);
+
+    for map in process.maps()? {
+        // only code counts, data files and shared memory are expected to come and go
+        if !map.perms.contains(MMPermissions::EXECUTE) {
+            continue;
+        }
+        let MMapPath::Path(path) = &map.pathname else {
+            continue;
+        };
+        if path.starts_with("/memfd:") {
+            continue;
+        }
+        check(path, map.inode);
+    }
+
+    Ok(outdated.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::MyProcessID;
+
+    #[test]
+    fn test_outdated_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let sleep = dir.path().join("sleep");
+        let sleep_bin = which_sleep().expect("sleep should be installed");
+        fs::copy(&sleep_bin, &sleep).unwrap();
+
+        let spawn = || {
+            std::process::Command::new(&sleep)
+                .arg("10")
+                .spawn()
+                .expect("Failed to spawn process")
+        };
+        let outdated = |child: &std::process::Child| {
+            std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for the exec
+            let process = Process::new(child.id() as MyProcessID).unwrap();
+            outdated_files_of(&process, &mut InodeMemo::new()).unwrap()
+        };
+
+        let mut replaced = spawn();
+        assert_eq!(outdated(&replaced), []);
+
+        // an upgrade writes the new version next to the old one and renames it over
+        let upgrade = dir.path().join("sleep.new");
+        fs::copy(&sleep_bin, &upgrade).unwrap();
+        fs::rename(&upgrade, &sleep).unwrap();
+        assert_eq!(
+            outdated(&replaced),
+            [OutdatedFile {
+                path: sleep.clone(),
+                staleness: Staleness::Replaced,
+            }]
+        );
+
+        let mut deleted = spawn();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        fs::remove_file(&sleep).unwrap();
+        assert_eq!(
+            outdated(&deleted),
+            [OutdatedFile {
+                path: sleep,
+                staleness: Staleness::Deleted,
+            }]
+        );
+
+        for child in [&mut replaced, &mut deleted] {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn which_sleep() -> Option<PathBuf> {
+        ["/usr/bin/sleep", "/bin/sleep"]
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+    }
+}
@@ -0,0 +1,263 @@
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+
+use procfs::{
+    ProcResult,
+    process::{FDPermissions, FDTarget, MMapPath, Process},
+};
+
+use crate::manager::{MyProcessID, traits::process_name::ProcessName};
+
+/// Why a process is using a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileUseReason {
+    /// An open file descriptor and the access it was opened with.
+    Fd {
+        fd: i32,
+        read: bool,
+        write: bool,
+    },
+    Cwd,
+    Root,
+    Exe,
+    Mmap,
+}
+
+impl fmt::Display for FileUseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fd { fd, read, write } => {
+                let mode = match (read, write) {
+                    (true, true) => "rw",
+                    (true, false) => "r",
+                    (false, true) => "w",
+                    (false, false) => "-",
+                };
+                write!(f, "fd {fd} {mode}")
+            }
+            Self::Cwd => write!(f, "cwd"),
+            Self::Root => write!(f, "root"),
+            Self::Exe => write!(f, "exe"),
+            Self::Mmap => write!(f, "mmap"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileUse {
+    pub pid: MyProcessID,
+    pub name: String,
+    /// What the process actually uses, a file below the path that was looked up when it
+    /// is a directory or a mount point.
+    pub path: PathBuf,
+    pub reason: FileUseReason,
+}
+
+/// What counts as using the path that is looked up.
+#[derive(Debug)]
+enum Target {
+    /// The file itself, matched by device and inode so hard links and renamed files
+    /// count as well.
+    File { dev: u64, ino: u64 },
+    /// The directory and everything below it.
+    Tree { path: PathBuf, dev: u64, ino: u64 },
+    /// Anything on the filesystem mounted there, which is what keeps it from being
+    /// unmounted.
+    Mount { dev: u64 },
+}
+
+impl Target {
+    fn new(path: &Path) -> ProcResult<Self> {
+        let path = path.canonicalize()?;
+        let metadata = fs::metadata(&path)?;
+        if !metadata.is_dir() {
+            return Ok(Self::File {
+                dev: metadata.dev(),
+                ino: metadata.ino(),
+            });
+        }
+
+        let is_mount_point = Process::myself()?
+            .mountinfo()?
+            .into_iter()
+            .any(|mount| mount.mount_point == path);
+        if is_mount_point {
+            return Ok(Self::Mount {
+                dev: metadata.dev(),
+            });
+        }
+        Ok(Self::Tree {
+            path,
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
+    }
+
+    fn matches(&self, path: Option<&Path>, dev: u64, ino: u64) -> bool {
+        match self {
+            Self::File {
+                dev: target_dev,
+                ino: target_ino,
+            } => dev == *target_dev && ino == *target_ino,
+            Self::Tree {
+                path: target_path,
+                dev: target_dev,
+                ino: target_ino,
+            } => {
+                (dev == *target_dev && ino == *target_ino)
+                    || path.is_some_and(|path| path.starts_with(target_path))
+            }
+            Self::Mount { dev: target_dev } => dev == *target_dev,
+        }
+    }
+}
+
+pub trait GetProcsUsingFile {
+    /// The processes using the path, like `fuser -m` does for a mount point. Processes
+    /// whose open files cannot be inspected (those of other users, unless running as
+    /// root) are left out.
+    fn procs_using_file(&self) -> ProcResult<Vec<FileUse>>;
+}
+
+impl GetProcsUsingFile for Path {
+    fn procs_using_file(&self) -> ProcResult<Vec<FileUse>> {
+        let target = Target::new(self)?;
+        let mut uses = vec![];
+
+        for process in procfs::process::all_processes()?.flatten() {
+            let pid = process.pid();
+            let name = process.process_name();
+            let mut push = |path: PathBuf, reason| {
+                uses.push(FileUse {
+                    pid,
+                    name: name.clone(),
+                    path,
+                    reason,
+                });
+            };
+            let proc_entry = |entry: &str| fs::metadata(format!("/proc/{pid}/{entry}"));
+
+            for (entry, link, reason) in [
+                ("cwd", process.cwd(), FileUseReason::Cwd),
+                ("root", process.root(), FileUseReason::Root),
+                ("exe", process.exe(), FileUseReason::Exe),
+            ] {
+                let (Ok(link), Ok(metadata)) = (link, proc_entry(entry)) else {
+                    continue;
+                };
+                if target.matches(Some(&link), metadata.dev(), metadata.ino()) {
+                    push(link, reason);
+                }
+            }
+
+            if let Ok(fds) = process.fd() {
+                for fd in fds.flatten() {
+                    // sockets, pipes and anonymous inodes live on no real filesystem
+                    let FDTarget::Path(path) = &fd.target else {
+                        continue;
+                    };
+                    let Ok(metadata) = proc_entry(&format!("fd/{}", fd.fd)) else {
+                        continue;
+                    };
+                    if target.matches(Some(path), metadata.dev(), metadata.ino()) {
+                        push(
+                            path.clone(),
+                            FileUseReason::Fd {
+                                fd: fd.fd,
+                                read: fd.mode().contains(FDPermissions::READ),
+                                write: fd.mode().contains(FDPermissions::WRITE),
+                            },
+                        );
+                    }
+                }
+            }
+
+            if let Ok(maps) = process.maps() {
+                // a library is mapped several times, once per segment
+                let mut seen = HashSet::new();
+                for map in maps {
+                    let MMapPath::Path(path) = map.pathname else {
+                        continue;
+                    };
+                    let dev = libc::makedev(map.dev.0 as u32, map.dev.1 as u32);
+                    if seen.insert((dev, map.inode)) && target.matches(Some(&path), dev, map.inode)
+                    {
+                        push(path, FileUseReason::Mmap);
+                    }
+                }
+            }
+        }
+
+        Ok(uses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uses_of(path: &Path, pid: u32) -> Vec<String> {
+        path.procs_using_file()
+            .unwrap()
+            .into_iter()
+            .filter(|file_use| file_use.pid == pid as MyProcessID)
+            .map(|file_use| file_use.reason.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_procs_using_file_and_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("held");
+        std::fs::write(&file, "content").unwrap();
+
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("exec 5<> {}; exec sleep 10", file.display()))
+            .current_dir(dir.path())
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(std::time::Duration::from_millis(200)); // Give time for process to open fd
+
+        assert_eq!(uses_of(&file, child.id()), ["fd 5 rw"]);
+
+        let mut dir_uses = uses_of(dir.path(), child.id());
+        dir_uses.sort();
+        assert_eq!(dir_uses, ["cwd", "fd 5 rw"]);
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_procs_using_mapped_executable() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .expect("Failed to spawn process");
+
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let exe = Process::new(child.id() as MyProcessID)
+            .unwrap()
+            .exe()
+            .unwrap();
+        let uses = uses_of(&exe, child.id());
+        assert!(
+            uses.contains(&"exe".to_string()),
+            "Unexpected uses: {uses:?}"
+        );
+        assert!(
+            uses.contains(&"mmap".to_string()),
+            "Unexpected uses: {uses:?}"
+        );
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
@@ -11,16 +11,9 @@ impl GetUsingFiles for Process {
 
         if let Ok(fds) = self.fd() {
             for fd in fds.flatten() {
-                if let FDTarget::Path(fd_path) = &fd.target {
-                    if let Ok(canonical_fd_path) = fd_path.canonicalize() {
-                        files_using.push(canonical_fd_path);
-                    } else {
-                        tracing::warn!(
-                            "Failed to canonicalize fd path: {:?} for process: {}",
-                            fd_path,
-                            self.pid
-                        );
-                    }
+                // the kernel already resolves the link to an absolute path
+                if let FDTarget::Path(fd_path) = fd.target {
+                    files_using.push(fd_path);
                 };
             }
         }
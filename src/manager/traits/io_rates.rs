@@ -0,0 +1,19 @@
+use crate::manager::{MyProcess, io_sampler::IoSampler};
+
+pub trait SampleIoRates {
+    /// Fills in the I/O rates of every process whose counters could be read.
+    fn sample_io_rates(&mut self, io_sampler: &mut IoSampler);
+}
+
+impl SampleIoRates for Vec<MyProcess> {
+    fn sample_io_rates(&mut self, io_sampler: &mut IoSampler) {
+        io_sampler.begin_tick();
+        for process in self.iter_mut() {
+            let identity = process.identity();
+            process.io_rates = process
+                .io
+                .map(|counters| io_sampler.sample(identity, counters));
+        }
+        io_sampler.finish_tick();
+    }
+}
@@ -0,0 +1,68 @@
+use procfs::process::{Process, Status};
+
+use crate::manager::memory_usage::MemoryUsage;
+
+pub trait GetMemoryUsage {
+    /// Takes the status of the process, it is read once for everything it holds.
+    fn memory_usage(&self, status: Option<&Status>) -> MemoryUsage;
+}
+
+impl GetMemoryUsage for Process {
+    fn memory_usage(&self, status: Option<&Status>) -> MemoryUsage {
+        // status is readable for every process, smaps_rollup only for our own ones
+        let kib = |value: Option<u64>| value.map(|kib| kib.saturating_mul(1024));
+
+        let rollup = self.smaps_rollup().ok();
+        let rollup_value = |key: &str| {
+            rollup.as_ref().and_then(|rollup| {
+                rollup
+                    .memory_map_rollup
+                    .0
+                    .first()
+                    .and_then(|map| map.extension.map.get(key).copied())
+            })
+        };
+
+        let rss = kib(status.and_then(|s| s.vmrss))
+            .or_else(|| rollup_value("Rss"))
+            .unwrap_or(0);
+        let shared =
+            kib(status.and_then(|s| s.rssfile.zip(s.rssshmem).map(|(file, shmem)| file + shmem)))
+                .unwrap_or(0);
+        let uss = rollup_value("Private_Clean")
+            .zip(rollup_value("Private_Dirty"))
+            .map(|(clean, dirty)| clean + dirty);
+
+        MemoryUsage {
+            rss,
+            pss: rollup_value("Pss"),
+            uss,
+            shared,
+            swap: kib(status.and_then(|s| s.vmswap)).or_else(|| rollup_value("Swap")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_usage_of_own_process() {
+        let myself = Process::myself().unwrap();
+        let memory = myself.memory_usage(myself.status().ok().as_ref());
+        let pss = memory
+            .pss
+            .expect("PSS of our own process should be readable");
+        let uss = memory
+            .uss
+            .expect("USS of our own process should be readable");
+
+        assert!(memory.rss > 0);
+        assert!(
+            uss <= pss && pss <= memory.rss,
+            "Unexpected usage: {memory:?}"
+        );
+        assert!(memory.shared <= memory.rss);
+    }
+}
@@ -1,10 +1,16 @@
+pub use task_manager_rs_helper::process_name;
+
 pub mod command_string;
 pub mod cpu_percent;
+pub mod find_outdated_files;
+pub mod get_procs_using_file;
 pub mod get_using_files;
-pub mod memory_bytes;
-pub mod process_name;
+pub mod io_rates;
+pub mod memory_usage;
+pub mod security_label;
 pub mod sort_my_processes;
 pub mod to_my_processes;
+pub mod to_my_threads;
 pub mod to_root_parents_and_children;
 pub mod to_standard_list_view_items;
 pub mod username;
@@ -0,0 +1,54 @@
+use std::{io::ErrorKind, path::Path};
+
+use procfs::process::{Process, Task};
+
+use crate::manager::security_label::SecurityLabel;
+
+pub trait GetSecurityLabel {
+    fn security_label(&self) -> SecurityLabel;
+}
+
+impl GetSecurityLabel for Process {
+    fn security_label(&self) -> SecurityLabel {
+        label_in(&Path::new("/proc").join(self.pid().to_string()))
+    }
+}
+
+/// Threads can change their own label, e.g. through `aa_change_hat`.
+impl GetSecurityLabel for Task {
+    fn security_label(&self) -> SecurityLabel {
+        label_in(&Path::new("/proc").join(format!("{}/task/{}", self.pid, self.tid)))
+    }
+}
+
+fn label_in(dir: &Path) -> SecurityLabel {
+    // with stacked modules attr/current belongs to the first one, AppArmor has its own
+    // directory since Linux 5.8
+    for attr in ["attr/apparmor/current", "attr/current"] {
+        match std::fs::read(dir.join(attr)) {
+            Ok(current) => return SecurityLabel::parse(&current),
+            // the kernel refuses to read an attribute no active module provides, AppArmor
+            // may be built in but disabled while SELinux labels the process
+            Err(e) if e.kind() == ErrorKind::NotFound || e.raw_os_error() == Some(libc::EINVAL) => {
+                continue;
+            }
+            Err(_) => return SecurityLabel::Unreadable,
+        }
+    }
+    SecurityLabel::None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threads_have_the_label_of_their_process() {
+        let myself = Process::myself().unwrap();
+        let label = myself.security_label();
+        assert_ne!(label, SecurityLabel::Unreadable);
+        for task in myself.tasks().unwrap().flatten() {
+            assert_eq!(task.security_label(), label);
+        }
+    }
+}
@@ -1,11 +1,14 @@
-use crate::manager::{Column, MyProcess, SortOrder};
+use crate::{
+    manager::{Column, MyProcess, SortOrder, memory_usage::MemoryMetric},
+    utils::cpu_list::cpu_count,
+};
 
 pub trait SortMyProcesses {
-    fn sort(&mut self, sort_by: &Column, sort_order: &SortOrder);
+    fn sort(&mut self, sort_by: &Column, sort_order: &SortOrder, memory_metric: MemoryMetric);
 }
 
 impl SortMyProcesses for Vec<&MyProcess> {
-    fn sort(&mut self, sort_by: &Column, sort_order: &SortOrder) {
+    fn sort(&mut self, sort_by: &Column, sort_order: &SortOrder, memory_metric: MemoryMetric) {
         match sort_by {
             Column::Name => {
                 self.sort_by(|a, b| {
@@ -21,8 +24,68 @@ impl SortMyProcesses for Vec<&MyProcess> {
             Column::CPU => {
                 self.sort_by_key(|p| (p.cpu_percent * 100.0) as i32);
             }
+            Column::CPUTotal => {
+                self.sort_by_key(|p| (p.cpu_total_percent * 100.0) as i32);
+            }
+            Column::LastCPU => {
+                self.sort_by_key(|p| p.last_cpu);
+            }
+            Column::Affinity => {
+                self.sort_by_key(|p| p.allowed_cpus.as_deref().map(cpu_count));
+            }
+            Column::Threads => {
+                self.sort_by_key(|p| p.threads);
+            }
+            Column::Priority => {
+                self.sort_by_key(|p| p.priority);
+            }
+            Column::Nice => {
+                self.sort_by_key(|p| p.nice);
+            }
+            Column::Policy => {
+                self.sort_by_key(|p| p.sched_policy);
+            }
+            Column::IOPriority => {
+                self.sort_by_key(|p| p.io_priority);
+            }
             Column::Memory => {
-                self.sort_by_key(|p| p.memory_bytes);
+                self.sort_by_key(|p| p.memory.get(memory_metric));
+            }
+            Column::RSS => {
+                self.sort_by_key(|p| p.memory.rss);
+            }
+            Column::PSS => {
+                self.sort_by_key(|p| p.memory.pss);
+            }
+            Column::USS => {
+                self.sort_by_key(|p| p.memory.uss);
+            }
+            Column::Shared => {
+                self.sort_by_key(|p| p.memory.shared);
+            }
+            Column::Swap => {
+                self.sort_by_key(|p| p.memory.swap);
+            }
+            Column::DiskRead => {
+                self.sort_by_key(|p| p.io_rates.map(|rates| rates.read_bytes));
+            }
+            Column::DiskWrite => {
+                self.sort_by_key(|p| p.io_rates.map(|rates| rates.write_bytes));
+            }
+            Column::ReadCalls => {
+                self.sort_by_key(|p| p.io_rates.map(|rates| rates.read_calls));
+            }
+            Column::WriteCalls => {
+                self.sort_by_key(|p| p.io_rates.map(|rates| rates.write_calls));
+            }
+            Column::CancelledWrites => {
+                self.sort_by_key(|p| p.io_rates.map(|rates| rates.cancelled_write_bytes));
+            }
+            Column::Outdated => {
+                self.sort_by_key(|p| p.outdated_files.as_ref().map(Vec::len));
+            }
+            Column::Label => {
+                self.sort_by_key(|p| p.security_label.clone());
             }
             Column::ParentID => {
                 self.sort_by_key(|p| p.parent_id);
@@ -1,48 +1,95 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use procfs::{WithCurrentSystemInfo, process::ProcessesIter};
 use tracing::warn;
 
 use crate::manager::{
     MyProcess,
+    credentials::{Credentials, user_name},
+    io_sampler::IoCounters,
+    scheduling::{SchedPolicy, io_priority_of},
     traits::{
-        command_string::CommandString, cpu_percent::CpuPercent, get_using_files::GetUsingFiles,
-        memory_bytes::MemoryBytes, process_name::ProcessName, username::Username,
+        command_string::CommandString, memory_usage::GetMemoryUsage, process_name::ProcessName,
+        security_label::GetSecurityLabel, username::Username,
     },
 };
 
 pub trait ToMyProcesses {
-    fn to_my_processes(self) -> Vec<MyProcess>;
+    /// Returns `None` as soon as `cancelled` is set.
+    fn to_my_processes(self, cancelled: &AtomicBool) -> Option<Vec<MyProcess>>;
 }
 
 impl ToMyProcesses for ProcessesIter {
-    fn to_my_processes(self) -> Vec<MyProcess> {
-        self.into_iter()
-            .filter_map(|process| {
-                let Ok(process) = process else {
-                    warn!("Failed to get process: {:?}", process.err());
-                    return None;
-                };
-
-                let Ok(ref stat) = process.stat() else {
-                    warn!(
-                        "Failed to get process stat for PID {}: {:?}",
-                        process.pid(),
-                        process.stat().err()
-                    );
-                    return None;
-                };
-                Some(MyProcess {
-                    name: process.process_name(),
-                    id: process.pid(),
-                    parent_id: stat.ppid,
-                    cpu_percent: stat.cpu_percent().unwrap_or_default(),
-                    memory_bytes: process.memory_bytes(),
-                    state: stat.state().into(),
-                    start_time: stat.starttime().get().ok(),
-                    user: process.username(),
-                    command: process.command(),
-                    files_using: process.using_files(),
-                })
-            })
-            .collect()
+    fn to_my_processes(self, cancelled: &AtomicBool) -> Option<Vec<MyProcess>> {
+        let mut my_processes = vec![];
+
+        for process in self {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            let Ok(process) = process else {
+                warn!("Failed to get process: {:?}", process.err());
+                continue;
+            };
+
+            let Ok(ref stat) = process.stat() else {
+                warn!(
+                    "Failed to get process stat for PID {}: {:?}",
+                    process.pid(),
+                    process.stat().err()
+                );
+                continue;
+            };
+            let status = process.status().ok();
+            let credentials = status.as_ref().map(Credentials::from);
+            // setuid programs and services that switched users act as someone else
+            let user = match &credentials {
+                Some(credentials) if credentials.uid.effective != credentials.uid.real => {
+                    format!(
+                        "{} (as {})",
+                        user_name(credentials.uid.real),
+                        user_name(credentials.uid.effective)
+                    )
+                }
+                Some(credentials) => user_name(credentials.uid.real),
+                None => process.username(),
+            };
+            my_processes.push(MyProcess {
+                name: process.process_name(),
+                id: process.pid(),
+                start_ticks: stat.starttime,
+                parent_id: stat.ppid,
+                thread_of: None,
+                threads: stat.num_threads,
+                priority: stat.priority,
+                nice: stat.nice,
+                sched_policy: stat
+                    .policy
+                    .zip(stat.rt_priority)
+                    .map(|(policy, rt_priority)| SchedPolicy::from_stat(policy, rt_priority)),
+                io_priority: io_priority_of(process.pid()).ok(),
+                cpu_ticks: stat.utime + stat.stime,
+                cpu_percent: 0.0,
+                cpu_total_percent: 0.0,
+                last_cpu: stat.processor,
+                allowed_cpus: status
+                    .as_ref()
+                    .and_then(|status| status.cpus_allowed_list.clone()),
+                memory: process.memory_usage(status.as_ref()),
+                io: process.io().ok().map(IoCounters::from),
+                io_rates: None,
+                outdated_files: None,
+                file_uses: vec![],
+                credentials,
+                security_label: process.security_label(),
+                state: stat.state().into(),
+                start_time: stat.starttime().get().ok(),
+                user,
+                command: process.command(),
+            });
+        }
+
+        Some(my_processes)
     }
 }
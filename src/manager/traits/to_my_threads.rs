@@ -0,0 +1,66 @@
+use procfs::{WithCurrentSystemInfo, process::Process};
+
+use crate::manager::{
+    MyProcess,
+    scheduling::{SchedPolicy, io_priority_of},
+    traits::security_label::GetSecurityLabel,
+};
+
+pub trait ToMyThreads {
+    /// The threads of the process from `/proc/<pid>/task`, the main thread included.
+    fn to_my_threads(&self) -> Vec<MyProcess>;
+}
+
+impl ToMyThreads for MyProcess {
+    fn to_my_threads(&self) -> Vec<MyProcess> {
+        // the PID may have been given to another process since the scan
+        if self.identity().verify().is_err() {
+            return vec![];
+        }
+        let Ok(tasks) = Process::new(self.id).and_then(|process| process.tasks()) else {
+            return vec![];
+        };
+
+        tasks
+            .flatten()
+            .filter_map(|task| {
+                let stat = task.stat().ok()?;
+                Some(MyProcess {
+                    name: stat.comm.clone(),
+                    id: task.tid,
+                    start_ticks: stat.starttime,
+                    parent_id: self.id,
+                    thread_of: Some(self.id),
+                    threads: 0,
+                    // threads are scheduled on their own, they can differ from the process
+                    priority: stat.priority,
+                    nice: stat.nice,
+                    sched_policy: stat
+                        .policy
+                        .zip(stat.rt_priority)
+                        .map(|(policy, rt_priority)| SchedPolicy::from_stat(policy, rt_priority)),
+                    io_priority: io_priority_of(task.tid).ok(),
+                    cpu_ticks: stat.utime + stat.stime,
+                    cpu_percent: 0.0,
+                    cpu_total_percent: 0.0,
+                    last_cpu: stat.processor,
+                    allowed_cpus: task
+                        .status()
+                        .ok()
+                        .and_then(|status| status.cpus_allowed_list),
+                    memory: self.memory,
+                    io: None,
+                    io_rates: None,
+                    outdated_files: None,
+                    file_uses: vec![],
+                    credentials: None,
+                    security_label: task.security_label(),
+                    state: stat.state().into(),
+                    start_time: stat.starttime().get().ok(),
+                    user: self.user.clone(),
+                    command: self.command.clone(),
+                })
+            })
+            .collect()
+    }
+}
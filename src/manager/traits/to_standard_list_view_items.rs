@@ -1,39 +1,106 @@
-use slint::{ModelRc, SharedString, StandardListViewItem, VecModel};
+use slint::{SharedString, StandardListViewItem};
 
-use crate::{manager::MyProcess, utils::human_readable_byte::human_readable_byte};
+use crate::{
+    manager::{MyProcess, io_sampler::IoRates, memory_usage::MemoryMetric},
+    utils::{cpu_list::format_cpu_list, human_readable_byte::human_readable_byte},
+};
 
 pub trait ToStandardListViewItems {
-    fn to_standard_list_view_items(&self) -> ModelRc<ModelRc<StandardListViewItem>>;
+    /// Plain rows rather than a model, so they can be built away from the event loop.
+    fn to_standard_list_view_items(
+        &self,
+        memory_metric: MemoryMetric,
+    ) -> Vec<Vec<StandardListViewItem>>;
 }
 
 impl ToStandardListViewItems for Vec<(MyProcess, usize)> {
-    fn to_standard_list_view_items(&self) -> ModelRc<ModelRc<StandardListViewItem>> {
-        ModelRc::new(VecModel::from(
-            self.iter()
-                .map(|(process, indent)| {
-                    ModelRc::new(VecModel::from(
-                        vec![
-                            format!("{}{}", "  ".repeat(indent * 2), process.name),
-                            format!("{}", process.id),
-                            format!("{:.1}%", process.cpu_percent),
-                            format!("{}", human_readable_byte(process.memory_bytes)),
-                            format!("{}", process.parent_id),
-                            format!("{:?}", process.state),
-                            // format!("{} seconds", process.start_time),
-                            process
-                                .start_time
-                                .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
-                                .unwrap_or_else(|| "N/A".to_string()),
-                            format!("{}", process.user),
-                            process.command.clone(),
-                        ]
-                        .into_iter()
-                        .map(SharedString::from)
-                        .map(StandardListViewItem::from)
-                        .collect::<Vec<_>>(),
-                    ))
-                })
-                .collect::<Vec<_>>(),
-        ))
+    fn to_standard_list_view_items(
+        &self,
+        memory_metric: MemoryMetric,
+    ) -> Vec<Vec<StandardListViewItem>> {
+        let bytes = |bytes: Option<u64>| {
+            bytes
+                .map(human_readable_byte)
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+        let io_rate = |process: &MyProcess, rate: fn(&IoRates) -> String| {
+            process
+                .io_rates
+                .as_ref()
+                .map(rate)
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+
+        let affinity = |process: &MyProcess| {
+            process
+                .allowed_cpus
+                .as_deref()
+                .map_or_else(|| "N/A".to_string(), format_cpu_list)
+        };
+
+        self.iter()
+            .map(|(process, indent)| {
+                // threads share the memory of their process, the I/O is not sampled per thread
+                let per_process = |value: String| match process.thread_of {
+                    Some(_) => String::new(),
+                    None => value,
+                };
+                vec![
+                    format!("{}{}", "  ".repeat(indent * 2), process.name),
+                    format!("{}", process.id),
+                    format!("{:.1}%", process.cpu_percent),
+                    format!("{:.1}%", process.cpu_total_percent),
+                    process
+                        .last_cpu
+                        .map_or_else(|| "N/A".to_string(), |cpu| cpu.to_string()),
+                    affinity(process),
+                    per_process(process.threads.to_string()),
+                    process.priority.to_string(),
+                    process.nice.to_string(),
+                    process
+                        .sched_policy
+                        .map_or_else(|| "N/A".to_string(), |policy| policy.to_string()),
+                    process
+                        .io_priority
+                        .map_or_else(|| "N/A".to_string(), |priority| priority.to_string()),
+                    per_process(bytes(process.memory.get(memory_metric))),
+                    per_process(human_readable_byte(process.memory.rss)),
+                    per_process(bytes(process.memory.pss)),
+                    per_process(bytes(process.memory.uss)),
+                    per_process(human_readable_byte(process.memory.shared)),
+                    per_process(bytes(process.memory.swap)),
+                    per_process(io_rate(process, |rates| {
+                        format!("{}/s", human_readable_byte(rates.read_bytes))
+                    })),
+                    per_process(io_rate(process, |rates| {
+                        format!("{}/s", human_readable_byte(rates.write_bytes))
+                    })),
+                    per_process(io_rate(process, |rates| format!("{}/s", rates.read_calls))),
+                    per_process(io_rate(process, |rates| format!("{}/s", rates.write_calls))),
+                    per_process(io_rate(process, |rates| {
+                        format!("{}/s", human_readable_byte(rates.cancelled_write_bytes))
+                    })),
+                    format!("{}", process.parent_id),
+                    format!("{:?}", process.state),
+                    // format!("{} seconds", process.start_time),
+                    process
+                        .start_time
+                        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_else(|| "N/A".to_string()),
+                    format!("{}", process.user),
+                    per_process(match &process.outdated_files {
+                        None => "N/A".to_string(),
+                        Some(files) if files.is_empty() => "No".to_string(),
+                        Some(files) => format!("Yes ({})", files.len()),
+                    }),
+                    process.security_label.to_string(),
+                    process.command.clone(),
+                ]
+                .into_iter()
+                .map(SharedString::from)
+                .map(StandardListViewItem::from)
+                .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
     }
 }
@@ -0,0 +1,106 @@
+/// The capabilities by bit number, as `capabilities(7)` names them.
+const CAPABILITY_NAMES: [&str; 41] = [
+    "cap_chown",
+    "cap_dac_override",
+    "cap_dac_read_search",
+    "cap_fowner",
+    "cap_fsetid",
+    "cap_kill",
+    "cap_setgid",
+    "cap_setuid",
+    "cap_setpcap",
+    "cap_linux_immutable",
+    "cap_net_bind_service",
+    "cap_net_broadcast",
+    "cap_net_admin",
+    "cap_net_raw",
+    "cap_ipc_lock",
+    "cap_ipc_owner",
+    "cap_sys_module",
+    "cap_sys_rawio",
+    "cap_sys_chroot",
+    "cap_sys_ptrace",
+    "cap_sys_pacct",
+    "cap_sys_admin",
+    "cap_sys_boot",
+    "cap_sys_nice",
+    "cap_sys_resource",
+    "cap_sys_time",
+    "cap_sys_tty_config",
+    "cap_mknod",
+    "cap_lease",
+    "cap_audit_write",
+    "cap_audit_control",
+    "cap_setfcap",
+    "cap_mac_override",
+    "cap_mac_admin",
+    "cap_syslog",
+    "cap_wake_alarm",
+    "cap_block_suspend",
+    "cap_audit_read",
+    "cap_perfmon",
+    "cap_bpf",
+    "cap_checkpoint_restore",
+];
+
+/// The names of the capabilities in a set from `/proc/<pid>/status`, the bits this
+/// kernel knows about but this list does not are named by number.
+pub fn capability_names(set: u64) -> Vec<String> {
+    (0..u64::BITS)
+        .filter(|bit| set & (1 << bit) != 0)
+        .map(|bit| {
+            CAPABILITY_NAMES
+                .get(bit as usize)
+                .map_or_else(|| format!("cap_{bit}"), |name| name.to_string())
+        })
+        .collect()
+}
+
+/// Lists the capabilities of a set, `all` when it holds every known one as root
+/// processes usually do.
+pub fn describe_capabilities(set: u64) -> String {
+    let known = (1u64 << CAPABILITY_NAMES.len()) - 1;
+    match set {
+        0 => "none".to_string(),
+        set if set & known == known => "all".to_string(),
+        set => capability_names(set).join(", "),
+    }
+}
+
+/// The bit of a capability, with or without its `cap_` prefix and in any case.
+pub fn capability_bit(name: &str) -> Option<u32> {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_prefix("cap_").unwrap_or(&name);
+    CAPABILITY_NAMES
+        .iter()
+        .position(|known| known.strip_prefix("cap_") == Some(name))
+        .map(|bit| bit as u32)
+        .or_else(|| name.parse().ok().filter(|bit| *bit < u64::BITS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_capabilities() {
+        // cap_net_bind_service and cap_net_admin
+        let set = (1 << 10) | (1 << 12);
+        assert_eq!(
+            capability_names(set),
+            ["cap_net_bind_service", "cap_net_admin"]
+        );
+        assert_eq!(
+            describe_capabilities(set),
+            "cap_net_bind_service, cap_net_admin"
+        );
+        assert_eq!(describe_capabilities(0), "none");
+        assert_eq!(describe_capabilities(0x000001ffffffffff), "all");
+        assert_eq!(capability_names(1 << 45), ["cap_45"]);
+
+        assert_eq!(capability_bit("cap_net_admin"), Some(12));
+        assert_eq!(capability_bit("NET_ADMIN"), Some(12));
+        assert_eq!(capability_bit("cap_sys_admin"), Some(21));
+        assert_eq!(capability_bit("cap_net_everything"), None);
+    }
+}
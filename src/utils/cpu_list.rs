@@ -0,0 +1,84 @@
+use procfs::{CpuInfo, Current};
+
+/// Parses a CPU list as cpuset(7) writes them, e.g. `0-3,6`, into inclusive ranges.
+pub fn parse_cpu_list(list: &str) -> Option<Vec<(u32, u32)>> {
+    let list = list.trim();
+    if list.is_empty() {
+        return Some(vec![]);
+    }
+    list.split(',')
+        .map(|range| match range.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+                (first <= last).then_some((first, last))
+            }
+            None => range.parse().ok().map(|cpu| (cpu, cpu)),
+        })
+        .collect()
+}
+
+/// Writes inclusive ranges of CPUs back as a CPU list.
+pub fn format_cpu_list(ranges: &[(u32, u32)]) -> String {
+    ranges
+        .iter()
+        .map(|&(first, last)| {
+            if first == last {
+                first.to_string()
+            } else {
+                format!("{first}-{last}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Merges sorted CPU numbers into inclusive ranges.
+pub fn cpu_ranges(cpus: impl IntoIterator<Item = u32>) -> Vec<(u32, u32)> {
+    let mut ranges: Vec<(u32, u32)> = vec![];
+    for cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, last)) if last.checked_add(1) == Some(cpu) => *last = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges
+}
+
+pub fn cpus_of(ranges: &[(u32, u32)]) -> impl Iterator<Item = u32> + '_ {
+    ranges.iter().flat_map(|&(first, last)| first..=last)
+}
+
+pub fn cpu_count(ranges: &[(u32, u32)]) -> u32 {
+    ranges.iter().map(|&(first, last)| last - first + 1).sum()
+}
+
+/// The CPUs processes can currently be scheduled on, CPUs taken offline are left out.
+pub fn online_cpus() -> Vec<u32> {
+    let ranges = std::fs::read_to_string("/sys/devices/system/cpu/online")
+        .ok()
+        .and_then(|list| parse_cpu_list(&list))
+        .unwrap_or_else(|| {
+            let cores = CpuInfo::current().map_or(1, |info| info.num_cores()) as u32;
+            vec![(0, cores.max(1) - 1)]
+        });
+    cpus_of(&ranges).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_list_round_trip() {
+        let ranges = parse_cpu_list("0-3,6,8-9\n").unwrap();
+        assert_eq!(ranges, [(0, 3), (6, 6), (8, 9)]);
+        assert_eq!(format_cpu_list(&ranges), "0-3,6,8-9");
+        assert_eq!(cpu_count(&ranges), 7);
+        assert_eq!(cpu_ranges(cpus_of(&ranges)), ranges);
+        assert_eq!(cpu_ranges([1, 2, 4]), [(1, 2), (4, 4)]);
+
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("3-1"), None);
+        assert_eq!(parse_cpu_list("0,a"), None);
+    }
+}
@@ -1,3 +1,6 @@
+pub mod capabilities;
+pub mod cpu_list;
 pub mod human_readable_byte;
 pub mod parse_search_query;
+pub mod parse_signal;
 pub mod vec_take;
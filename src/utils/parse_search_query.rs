@@ -31,6 +31,28 @@ pub fn parse_search_query(query: &str) -> Vec<Search> {
         .collect()
 }
 
+/// The value of a `@port` or `@rport` search, either `8080` or `:8080` as addresses are
+/// written.
+pub fn parse_port(value: &str) -> Option<u16> {
+    value.trim().trim_start_matches(':').parse().ok()
+}
+
+/// The value of an `@env` search, `KEY` matches the processes that have the variable set
+/// and `KEY=value` those whose value of it contains `value`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EnvQuery<'a> {
+    pub key: &'a str,
+    pub value: Option<&'a str>,
+}
+
+pub fn parse_env_query(value: &str) -> Option<EnvQuery<'_>> {
+    let (key, value) = match value.split_once('=') {
+        Some((key, value)) => (key.trim(), Some(value)),
+        None => (value.trim(), None),
+    };
+    (!key.is_empty()).then_some(EnvQuery { key, value })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,4 +70,39 @@ mod tests {
         assert_eq!(searches[2].column, "pid");
         assert_eq!(searches[2].value, "123");
     }
+
+    #[test]
+    fn test_parse_port_search() {
+        let searches = parse_search_query("@port :8080, @name foo");
+        assert_eq!(searches[0].column, "port");
+        assert_eq!(parse_port(searches[0].value), Some(8080));
+        assert_eq!(parse_port("443"), Some(443));
+        assert_eq!(parse_port("http"), None);
+        assert_eq!(parse_port("70000"), None);
+    }
+
+    #[test]
+    fn test_parse_env_search() {
+        let searches = parse_search_query("@env RUST_LOG=debug, @env LD_LIBRARY_PATH, @env =x");
+        assert_eq!(
+            parse_env_query(searches[0].value),
+            Some(EnvQuery {
+                key: "RUST_LOG",
+                value: Some("debug")
+            })
+        );
+        assert_eq!(
+            parse_env_query(searches[1].value),
+            Some(EnvQuery {
+                key: "LD_LIBRARY_PATH",
+                value: None
+            })
+        );
+        assert_eq!(parse_env_query(searches[2].value), None);
+        assert_eq!(
+            parse_env_query("OPTS=a=b").unwrap().value,
+            Some("a=b"),
+            "Only the first = separates the key"
+        );
+    }
 }
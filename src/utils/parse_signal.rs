@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use nix::sys::signal::Signal;
+
+/// Parses a signal given by name (`SIGHUP`, `hup`), by number (`15`) or as an offset
+/// into the realtime range (`SIGRTMIN+3`, `RTMAX-1`).
+pub fn parse_signal(signal: &str) -> Option<i32> {
+    let signal = signal.trim().to_uppercase();
+    if signal.is_empty() {
+        return None;
+    }
+
+    if let Ok(number) = signal.parse::<i32>() {
+        return (1..=libc::SIGRTMAX()).contains(&number).then_some(number);
+    }
+
+    let name = signal.strip_prefix("SIG").unwrap_or(&signal);
+    if let Some(offset) = name.strip_prefix("RTMIN") {
+        return realtime_signal(libc::SIGRTMIN(), offset);
+    }
+    if let Some(offset) = name.strip_prefix("RTMAX") {
+        return realtime_signal(libc::SIGRTMAX(), offset);
+    }
+
+    Signal::from_str(&format!("SIG{name}"))
+        .ok()
+        .map(|signal| signal as i32)
+}
+
+fn realtime_signal(base: i32, offset: &str) -> Option<i32> {
+    let offset = offset.replace(' ', "");
+    let number = match offset.as_str() {
+        "" => base,
+        _ if offset.starts_with('+') || offset.starts_with('-') => {
+            base.checked_add(offset.parse::<i32>().ok()?)?
+        }
+        _ => return None,
+    };
+    (libc::SIGRTMIN()..=libc::SIGRTMAX())
+        .contains(&number)
+        .then_some(number)
+}
+
+/// The inverse of [`parse_signal`], realtime signals are named relative to `SIGRTMIN`.
+pub fn signal_name(signal: i32) -> String {
+    if let Ok(signal) = Signal::try_from(signal) {
+        return signal.as_str().to_string();
+    }
+    match signal - libc::SIGRTMIN() {
+        0 => "SIGRTMIN".to_string(),
+        offset if signal <= libc::SIGRTMAX() && offset > 0 => format!("SIGRTMIN+{offset}"),
+        _ => format!("signal {signal}"),
+    }
+}
+
+/// Whether the signal can be meant for a single thread. The default action of the other
+/// signals (terminate, stop, continue) always applies to the whole process.
+pub fn is_thread_signal(signal: i32) -> bool {
+    signal == libc::SIGUSR1
+        || signal == libc::SIGUSR2
+        || (libc::SIGRTMIN()..=libc::SIGRTMAX()).contains(&signal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signal() {
+        assert_eq!(parse_signal("SIGTERM"), Some(libc::SIGTERM));
+        assert_eq!(parse_signal(" hup "), Some(libc::SIGHUP));
+        assert_eq!(parse_signal("sigusr2"), Some(libc::SIGUSR2));
+        assert_eq!(parse_signal("9"), Some(libc::SIGKILL));
+        assert_eq!(parse_signal("SIGRTMIN"), Some(libc::SIGRTMIN()));
+        assert_eq!(parse_signal("SIGRTMIN+3"), Some(libc::SIGRTMIN() + 3));
+        assert_eq!(parse_signal("rtmax - 1"), Some(libc::SIGRTMAX() - 1));
+        assert_eq!(parse_signal("SIGRTMAX+1"), None);
+        assert_eq!(parse_signal("SIGRTMIN3"), None);
+        assert_eq!(parse_signal("0"), None);
+        assert_eq!(parse_signal("SIGFOO"), None);
+        assert_eq!(parse_signal(""), None);
+    }
+
+    #[test]
+    fn test_signal_name() {
+        assert_eq!(signal_name(libc::SIGSTOP), "SIGSTOP");
+        assert_eq!(signal_name(libc::SIGRTMIN()), "SIGRTMIN");
+        assert_eq!(signal_name(libc::SIGRTMIN() + 2), "SIGRTMIN+2");
+        for signal in 1..=libc::SIGRTMAX() {
+            if Signal::try_from(signal).is_ok() || signal >= libc::SIGRTMIN() {
+                assert_eq!(parse_signal(&signal_name(signal)), Some(signal));
+            }
+        }
+    }
+}